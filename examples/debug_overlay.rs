@@ -0,0 +1,62 @@
+//! This showcases the layout debug overlay, press `F12` to toggle.
+
+use bevy::prelude::*;
+use bevy_rectray::{RectrayPlugin, util::RCommands};
+use bevy_rectray::debug::{DebugOverlay, DebugFilter, RectrayDebugPlugin};
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, init)
+        .add_systems(Update, filter)
+        .add_plugins(RectrayPlugin)
+        .add_plugins(RectrayDebugPlugin)
+        .run();
+}
+
+pub fn init(mut commands: RCommands) {
+    use bevy_rectray::dsl::prelude::*;
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    text!(commands {
+        anchor: Top,
+        text: "Press F12 to toggle, 1 to show all, 2 to show the \"stack\" subtree.",
+    });
+
+    vstack!(commands {
+        name: "stack",
+        margin: [8, 8],
+        padding: [16, 16],
+        child: rectangle! {
+            dimension: [200, 40],
+            color: color!(red),
+            event: EventFlags::LeftClick,
+        },
+        child: text! {
+            text: "Copied",
+            rotation: 0.2,
+        },
+        child: frame! {
+            dimension: [120, 60],
+            clipping: true,
+            hitbox: Hitbox::ellipse(1),
+        }
+    });
+
+    sprite!(commands {
+        anchor: Right,
+        offset: [-100, 0],
+        parent_anchor: Right,
+        dimension: [100, 100],
+        sprite: "square.png",
+    });
+}
+
+pub fn filter(keys: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<DebugOverlay>) {
+    if keys.just_pressed(KeyCode::Digit1) {
+        overlay.filter = DebugFilter::All;
+    }
+    if keys.just_pressed(KeyCode::Digit2) {
+        overlay.filter = DebugFilter::NamedSubtree("stack".into());
+    }
+}
@@ -3,6 +3,7 @@
 //! Note this just compiles to bevy_text, nothing fancy here.
 
 use bevy::{prelude::*, utils::HashMap};
+use bevy_defer::Object;
 use bevy_rectray::{RectrayPlugin, widgets::richtext::{RichTextBuilder, FontStyle}, util::RCommands};
 
 pub fn main() {
@@ -39,7 +40,21 @@ pub fn init(mut commands: RCommands) {
         (("roboto", FontStyle::Bold|FontStyle::Italic), commands.load("RobotoCondensed-BoldItalic.ttf")),
     ]);
     let default = commands.load("ComicNeue-Regular.ttf");
+    let check: Handle<Image> = commands.load("check.png");
+    let (link_send, link_recv) = signal::<Object, _>();
+    text!(commands {
+        anchor: Bottom,
+        offset: [0, 20],
+        text: "Click a link!",
+        signal: receiver::<ButtonClick>(link_recv),
+        system: |sig: Receiver<ButtonClick>, text: Ac<Text>| {
+            let id = sig.recv().await.get::<String>().unwrap_or_default();
+            text.set(move |text| format_widget!(text, "Clicked {}", id)).await?;
+        }
+    });
     let mut builder = RichTextBuilder::new(&mut commands, fonts)
+        .with_link_signal(link_send)
+        .with_images(HashMap::from([("check", check)]))
         .configure_size(default.clone(), 32.0)
         .with_font("roboto")
         .with_color(Color::WHITE);
@@ -51,6 +66,12 @@ Let's make our text {red:red} and {blue:blue.}
 
 We can make it **bold** or *italic,* even {red:red and ***bold and italic.***}
 
+We can add {link=glossary:__links__} to {link=rust:{orange:Rust}}.
+
+We can add images {img:check} inline.
+
+We can __underline__ or ~~strike through~~ {zip:__{orange:sev}eral {red:colors}__}.
+
 
 We can use a different {@comicneue: font}.
 
@@ -0,0 +1,90 @@
+//! This showcases `RectrayRoot`, laying out a HUD in each half of a split screen.
+
+use bevy::prelude::*;
+use bevy::render::camera::Viewport;
+use bevy::window::PrimaryWindow;
+use bevy_defer::Object;
+use bevy_rectray::{RectrayPlugin, RectrayRoot, util::RCommands};
+use bevy_rectray::events::RectrayCamera;
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, init)
+        .add_systems(Update, split_viewports)
+        .add_plugins(RectrayPlugin)
+        .run();
+}
+
+#[derive(Component)]
+pub struct Player(u32);
+
+pub fn init(mut commands: RCommands) {
+    use bevy_rectray::dsl::prelude::*;
+    for (index, x) in [(0, -1000.0), (1, 1000.0)] {
+        let mut camera = commands.spawn_bundle((
+            Player(index),
+            Camera2dBundle {
+                camera: Camera { order: index as isize, ..Default::default() },
+                transform: Transform::from_xyz(x, 0.0, 999.9),
+                ..Default::default()
+            },
+        ));
+        // Cursor positions are mapped through the root camera,
+        // `RectrayCamera` only decides the default camera.
+        if index == 0 {
+            camera.insert(RectrayCamera);
+        }
+        let camera = camera.id();
+        let (send, recv) = signal::<Object, _>();
+        vstack!(commands {
+            anchor: TopLeft,
+            offset: [16, -16],
+            margin: [0, 8],
+            extra: RectrayRoot::Camera(camera),
+            child: text! {
+                anchor: Left,
+                text: format!("Player {}", index + 1),
+            },
+            child: button! {
+                dimension: [160, 40],
+                event: EventFlags::LeftClick,
+                on_click: send,
+                child: rectangle! {
+                    dimension: Size2::FULL,
+                    color: color!(darkgray),
+                    z: -0.1,
+                },
+                child: text! {
+                    text: "Click Me!",
+                },
+            },
+        });
+        text!(commands {
+            anchor: Bottom,
+            offset: [0, 16],
+            text: "Waiting...",
+            extra: RectrayRoot::Camera(camera),
+            signal: receiver::<Invocation>(recv),
+            system: |sig: Receiver<Invocation>, text: Ac<Text>| {
+                sig.recv().await;
+                text.set(move |text| format_widget!(text, "Clicked by player {}!", index + 1)).await?;
+            }
+        });
+    }
+}
+
+pub fn split_viewports(
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&Player, &mut Camera)>,
+) {
+    let Ok(window) = window.get_single() else {return};
+    let size = UVec2::new(window.physical_width() / 2, window.physical_height());
+    for (player, mut camera) in cameras.iter_mut() {
+        camera.viewport = Some(Viewport {
+            physical_position: UVec2::new(size.x * player.0, 0),
+            physical_size: size,
+            ..Default::default()
+        });
+    }
+}
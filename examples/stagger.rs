@@ -0,0 +1,42 @@
+//! This showcases `Animation`, a staggered entrance of list items.
+
+use bevy::prelude::*;
+use bevy_rectray::{RectrayPlugin, util::RCommands};
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, init)
+        .add_plugins(RectrayPlugin)
+        .run();
+}
+
+pub fn init(mut commands: RCommands) {
+    use bevy_rectray::dsl::prelude::*;
+    commands.spawn_bundle(Camera2dBundle::default());
+    let colors = colors!(blue100, blue200, blue300, blue400, blue500, blue600);
+    vstack!(commands {
+        margin: [0, 8],
+        extra: transition!(
+            animate stagger 0.1 (
+                parallel(
+                    Opacity 0.4 Linear to 1.0,
+                    Offset 0.6 CubicOut to [0, 0],
+                ),
+                repeat 2 (
+                    Scale 0.1 QuadraticOut to [1.1, 1.1],
+                    Scale 0.1 QuadraticIn to [1, 1],
+                ),
+            );
+        ),
+        child: #rectangle! {
+            dimension: [200, 30],
+            color: #colors,
+            extra: transition!(
+                Opacity 0.4 Linear default 0.0;
+                Offset 0.6 Linear default [-100, 0];
+                Scale 0.1 Linear default [1, 1];
+            ),
+        },
+    });
+}
@@ -0,0 +1,73 @@
+/// Rendering wrapped, aligned and truncated text with `TextFragment`.
+
+use bevy::prelude::*;
+use bevy_rectray::{RectrayPlugin, util::RCommands, widgets::TextFragment};
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, init)
+        .add_plugins(RectrayPlugin)
+        .run();
+}
+
+static TEXT: &str = "The quick brown fox jumps over the lazy dog.\nPack my box with five dozen liquor jugs.";
+
+pub fn init(mut commands: RCommands) {
+    use bevy_rectray::dsl::prelude::*;
+    commands.spawn_bundle(Camera2dBundle::default());
+    let font = commands.load::<Font>("RobotoCondensed.ttf");
+
+    let fragments = [
+        ("Wrap", TextFragment::new(TEXT).with_wrap()),
+        ("Center", TextFragment::new(TEXT).with_wrap().with_alignment(JustifyText::Center)),
+        ("Right", TextFragment::new(TEXT).with_wrap().with_alignment(JustifyText::Right)),
+        ("Ellipsis", TextFragment::new(TEXT).with_wrap().with_ellipsis()),
+        ("No Wrap", TextFragment::new(TEXT).with_ellipsis()),
+    ];
+
+    let container = hstack!(commands {
+        offset: [0, 100],
+        margin: 20,
+    });
+    for (name, fragment) in fragments {
+        // `TextFragment` renders to its own image.
+        let image = commands.add_asset(Image::default());
+        let item = frame!(commands {
+            dimension: [160, 200],
+            child: text! {
+                anchor: Bottom,
+                parent_anchor: Top,
+                text: name,
+                color: color!(white),
+            },
+            child: rectangle! {
+                dimension: Size2::FULL,
+                color: color!(neutral800),
+                z: -1,
+            },
+            child: sprite! {
+                dimension: Size2::FULL,
+                sprite: image,
+                font_size: 18,
+                color: color!(white),
+                extra: fragment.with_font(font.clone()),
+            },
+        });
+        commands.entity(container).add_child(item);
+    }
+    // Copied dimension is sized by the text.
+    let image = commands.add_asset(Image::default());
+    sprite!(commands {
+        offset: [0, -200],
+        sprite: image,
+        font_size: 24,
+        color: color!(gold),
+        extra: TextFragment::new("A copied dimension is sized by its text.").with_font(font),
+        child: rectangle! {
+            dimension: Size2::FULL,
+            color: color!(neutral800),
+            z: -1,
+        },
+    });
+}
@@ -0,0 +1,74 @@
+//! This showcases `Timeline`, an entrance animation with multiple tracks and markers.
+
+use bevy::prelude::*;
+use bevy_defer::Object;
+use bevy_rectray::{RectrayPlugin, util::RCommands};
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, init)
+        .add_plugins(RectrayPlugin)
+        .run();
+}
+
+pub fn init(mut commands: RCommands) {
+    use bevy_rectray::dsl::prelude::*;
+    commands.spawn_bundle(Camera2dBundle::default());
+    let timeline = Timeline::new()
+        .track(Track::<Opacity>::new()
+            .key(0.0, 0.0, Easing::Linear)
+            .key(0.4, 1.0, Easing::Linear))
+        .track(Track::<Offset>::new()
+            .key(0.0, Vec2::new(0.0, -80.0), Easing::Ease(EaseFunction::CubicOut))
+            .key(0.6, Vec2::ZERO, Easing::Linear))
+        .track(Track::<Scale>::new()
+            .key(0.6, Vec2::ONE, Easing::Ease(EaseFunction::QuadraticInOut))
+            .key(0.8, Vec2::splat(1.2), Easing::Ease(EaseFunction::QuadraticInOut))
+            .key(1.0, Vec2::ONE, Easing::Linear))
+        .track(Track::<Color>::new()
+            .key(0.0, color!(darkgray), Easing::Linear)
+            .key(1.0, color!(teal), Easing::Linear))
+        .marker(0.6, "landed")
+        .marker(1.0, "done");
+    let (marker_send, marker_recv) = signal::<String, _>();
+    let (click_send, click_recv) = signal::<Object, _>();
+    rectangle!(commands {
+        dimension: [240, 120],
+        color: color!(darkgray),
+        extra: timeline,
+        signal: sender::<TimelineMarker>(marker_send),
+        signal: receiver::<Invocation>(click_recv),
+        system: |sig: Receiver<Invocation>, timeline: Ac<Timeline>| {
+            sig.recv().await;
+            timeline.set(|timeline| {
+                timeline.reverse();
+                timeline.play();
+            }).await?;
+        },
+        child: text! {
+            text: "Waiting...",
+            color: color!(black),
+            signal: receiver::<TimelineMarker>(marker_recv),
+            system: |sig: Receiver<TimelineMarker>, text: Ac<Text>| {
+                let name = sig.recv().await;
+                text.set(move |text| format_widget!(text, "Reached {}", name)).await?;
+            }
+        },
+    });
+    button!(commands {
+        anchor: Bottom,
+        offset: [0, 40],
+        dimension: [160, 40],
+        event: EventFlags::LeftClick,
+        on_click: click_send,
+        child: rectangle! {
+            dimension: Size2::FULL,
+            color: color!(darkgray),
+            z: -0.1,
+        },
+        child: text! {
+            text: "Reverse",
+        },
+    });
+}
@@ -0,0 +1,48 @@
+//! This showcases loading a widget tree from `menu.ui.ron`.
+
+use bevy::prelude::*;
+use bevy_rectray::{RectrayPlugin, util::RCommands};
+use bevy_rectray::asset::{UiAssetRoot, UiRegistry};
+use bevy_rectray::widgets::button::Button;
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                present_mode: bevy::window::PresentMode::AutoNoVsync,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+        .add_plugins(RectrayPlugin)
+        .add_systems(Startup, init)
+        .run();
+}
+
+
+pub fn init(mut commands: RCommands, mut registry: ResMut<UiRegistry>) {
+    use bevy_rectray::dsl::prelude::*;
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    registry.register_callback("make_button", |commands, entity| {
+        commands.entity(entity).insert(Button);
+    });
+
+    let handle = commands.load("menu.ui.ron");
+    frame!(commands {
+        dimension: [600, 400],
+        extra: UiAssetRoot::new(handle),
+    });
+
+    let clicked = commands.signal("start_game");
+    text!(commands {
+        anchor: Top,
+        text: "Press start.",
+        color: color!(gold),
+        signal: receiver::<ButtonClick>(clicked),
+        system: |x: Receiver<ButtonClick>, text: Ac<Text>| {
+            x.recv().await;
+            text.set(|text| format_widget!(text, "Game started!")).await?;
+        }
+    });
+}
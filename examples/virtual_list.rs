@@ -0,0 +1,80 @@
+//! This showcases a virtualized list with 100000 rows.
+
+use bevy::{prelude::*, diagnostic::FrameTimeDiagnosticsPlugin};
+use bevy_rectray::{Coloring, RectrayPlugin, size, util::{RCommands, WidgetBuilder}};
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                present_mode: bevy::window::PresentMode::AutoNoVsync,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+        .add_plugins(FrameTimeDiagnosticsPlugin)
+        .add_systems(Startup, init)
+        .add_plugins(RectrayPlugin)
+        .run();
+}
+
+
+pub fn init(mut commands: RCommands) {
+    use bevy_rectray::dsl::prelude::*;
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    text!(commands {
+        anchor: TopRight,
+        text: "FPS: 0.00",
+        color: color!(gold),
+        system: |fps: Fps, text: Ac<Text>| {
+            let fps = fps.get().await;
+            text.set(move |text| format_widget!(text, "FPS: {:.2}", fps)).await?;
+        }
+    });
+
+    let (send, recv) = signal();
+
+    text! (commands {
+        offset: [-300, 200],
+        color: color!(gold),
+        text: "Scroll the list! =>",
+        signal: receiver::<PositionFac>(recv),
+        system: |x: Receiver<PositionFac>, text: Ac<Text>| {
+            let s = x.recv().await;
+            text.write(format!("Scrolled to {:.2}! =>", s)).await?;
+        },
+    });
+
+    let row = WidgetBuilder::new(|commands: &mut RCommands, index: usize| {
+        text!(commands {
+            text: format!("Row {}", index),
+            color: if index % 2 == 1 {color!(gold)} else {color!(white)},
+        })
+    });
+
+    sprite! (commands {
+        dimension: [200, 400],
+        hitbox: Hitbox::rect(1),
+        sprite: commands.load("square.png"),
+        color: color!(darkgray),
+        event: EventFlags::MouseWheel,
+        extra: ScrollParent,
+        child: frame! {
+            anchor: Top,
+            dimension: size2!(100%, 0),
+            extra: Scrolling::Y,
+            // Rows scrolled out of view are reused for rows scrolled into view.
+            extra: VirtualList::new(100000, row, RowHeight::Fixed(size!(1.5 em)))
+                .with_rebind(|world: &mut World, row: Entity, index: usize| {
+                    if let Some(mut text) = world.get_mut::<Text>(row) {
+                        text.sections[0].value = format!("Row {}", index);
+                    }
+                    if let Some(mut coloring) = world.get_mut::<Coloring>(row) {
+                        coloring.color = if index % 2 == 1 {color!(gold)} else {color!(white)};
+                    }
+                }),
+            signal: sender::<PositionFac>(send),
+        }
+    });
+}
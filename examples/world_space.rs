@@ -0,0 +1,66 @@
+//! This showcases `RectrayRoot::Follow`, nameplates that follow moving sprites.
+
+use bevy::prelude::*;
+use bevy_rectray::{RectrayPlugin, RectrayRoot, util::RCommands};
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, init)
+        .add_systems(Update, orbit)
+        .add_plugins(RectrayPlugin)
+        .run();
+}
+
+#[derive(Component)]
+pub struct Orbit {
+    radius: f32,
+    speed: f32,
+}
+
+pub fn init(mut commands: RCommands) {
+    use bevy_rectray::dsl::prelude::*;
+    commands.spawn_bundle(Camera2dBundle::default());
+    for (index, color) in [Color::RED, Color::GREEN, Color::BLUE].into_iter().enumerate() {
+        let entity = commands.spawn_bundle((
+            Orbit {
+                radius: 100.0 + index as f32 * 80.0,
+                speed: 1.0 - index as f32 * 0.3,
+            },
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::new(40.0, 40.0)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )).id();
+        vstack!(commands {
+            anchor: Bottom,
+            offset: [0, 24],
+            margin: [0, 4],
+            extra: RectrayRoot::follow(entity),
+            child: text! {
+                text: format!("Unit {}", index + 1),
+            },
+            child: rectangle! {
+                dimension: [60, 6],
+                color: color!(darkgray),
+                child: rectangle! {
+                    anchor: Left,
+                    dimension: size2!(70%, 100%),
+                    color: color!(lime),
+                    z: 0.1,
+                }
+            },
+        });
+    }
+}
+
+pub fn orbit(time: Res<Time>, mut query: Query<(&Orbit, &mut Transform)>) {
+    for (orbit, mut transform) in query.iter_mut() {
+        let angle = time.elapsed_seconds() * orbit.speed;
+        transform.translation = (Vec2::from_angle(angle) * orbit.radius).extend(0.0);
+    }
+}
@@ -0,0 +1,249 @@
+use std::any::{Any, TypeId};
+use std::sync::Arc;
+use bevy::ecs::{component::Component, entity::Entity, system::Query};
+use bevy::hierarchy::Children;
+
+use super::{AnimationClock, Easing, Interpolate, Interpolation};
+
+/// Target of a single [`Animation`] step.
+struct Step<T: Interpolation> {
+    target: T::FrontEnd,
+    curve: Easing,
+    time: f32,
+}
+
+/// A composable animation value, played by an [`Animator`] on top of [`Interpolate`].
+///
+/// A step moves the `Interpolate<T>` of an entity, which must be present,
+/// with its own easing and time, and ignores the `Interpolate`'s defaults.
+///
+/// Construct with [`transition!`](crate::transition) or in rust:
+/// ```
+/// # use bevy::math::Vec2;
+/// # use bevy_rectray::dsl::prelude::*;
+/// # let _ =
+/// Animation::sequence([
+///     Animation::to::<Opacity>(1.0, Easing::Linear, 0.3),
+///     Animation::delay(0.2),
+///     Animation::parallel([
+///         Animation::to::<Offset>(Vec2::ZERO, Easing::Ease(EaseFunction::CubicOut), 0.5),
+///         Animation::to::<Scale>(Vec2::ONE, Easing::Linear, 0.5),
+///     ]),
+/// ]).repeat(2)
+/// # ;
+/// ```
+#[derive(Clone)]
+pub enum Animation {
+    /// Interpolate to a target.
+    Step {
+        id: TypeId,
+        time: f32,
+        step: Arc<dyn Any + Send + Sync>,
+    },
+    /// Wait for some time in seconds.
+    Delay(f32),
+    /// Play animations one after another.
+    Sequence(Vec<Animation>),
+    /// Play animations at the same time, ends when the longest one ends.
+    Parallel(Vec<Animation>),
+    /// Play an animation a number of times.
+    Repeat(usize, Box<Animation>),
+    /// Play an animation on each child instead of this entity,
+    /// each starting some time in seconds after the previous child.
+    Stagger(f32, Box<Animation>),
+}
+
+impl std::fmt::Debug for Animation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Step { id, time, .. } => f.debug_struct("Step").field("id", id).field("time", time).finish_non_exhaustive(),
+            Self::Delay(time) => f.debug_tuple("Delay").field(time).finish(),
+            Self::Sequence(items) => f.debug_tuple("Sequence").field(items).finish(),
+            Self::Parallel(items) => f.debug_tuple("Parallel").field(items).finish(),
+            Self::Repeat(count, item) => f.debug_tuple("Repeat").field(count).field(item).finish(),
+            Self::Stagger(delay, item) => f.debug_tuple("Stagger").field(delay).field(item).finish(),
+        }
+    }
+}
+
+impl Animation {
+    /// Interpolate to a target with an easing function in some time in seconds.
+    pub fn to<T: Interpolation>(target: T::FrontEnd, curve: Easing, time: f32) -> Self {
+        Animation::Step {
+            id: TypeId::of::<T>(),
+            time,
+            step: Arc::new(Step::<T> { target, curve, time }),
+        }
+    }
+
+    /// Wait for some time in seconds.
+    pub fn delay(time: f32) -> Self {
+        Animation::Delay(time)
+    }
+
+    /// Play animations one after another.
+    pub fn sequence(items: impl IntoIterator<Item = Animation>) -> Self {
+        Animation::Sequence(items.into_iter().collect())
+    }
+
+    /// Play animations at the same time.
+    pub fn parallel(items: impl IntoIterator<Item = Animation>) -> Self {
+        Animation::Parallel(items.into_iter().collect())
+    }
+
+    /// Play this animation `count` times.
+    pub fn repeat(self, count: usize) -> Self {
+        Animation::Repeat(count, Box::new(self))
+    }
+
+    /// Play this animation on each child, each starting `delay` seconds after the previous one.
+    pub fn stagger(self, delay: f32) -> Self {
+        Animation::Stagger(delay, Box::new(self))
+    }
+
+    /// Play another animation after this one.
+    pub fn then(self, other: Animation) -> Self {
+        match self {
+            Animation::Sequence(mut items) => {
+                items.push(other);
+                Animation::Sequence(items)
+            },
+            this => Animation::Sequence(vec![this, other]),
+        }
+    }
+
+    /// Flatten into steps on entities, returns the end time.
+    fn schedule(&self, entity: Entity, start: f32, children: &Query<&Children>, out: &mut Vec<Scheduled>) -> f32 {
+        match self {
+            Animation::Step { id, time, step } => {
+                out.push(Scheduled { at: start, entity, id: *id, step: step.clone() });
+                start + time
+            },
+            Animation::Delay(time) => start + time,
+            Animation::Sequence(items) => items.iter()
+                .fold(start, |at, item| item.schedule(entity, at, children, out)),
+            Animation::Parallel(items) => items.iter()
+                .map(|item| item.schedule(entity, start, children, out))
+                .fold(start, f32::max),
+            Animation::Repeat(count, item) => (0..*count)
+                .fold(start, |at, _| item.schedule(entity, at, children, out)),
+            Animation::Stagger(delay, item) => children.get(entity).into_iter()
+                .flat_map(|children| children.iter().copied())
+                .enumerate()
+                .map(|(index, child)| item.schedule(child, start + delay * index as f32, children, out))
+                .fold(start, f32::max),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Scheduled {
+    at: f32,
+    entity: Entity,
+    id: TypeId,
+    step: Arc<dyn Any + Send + Sync>,
+}
+
+/// Plays an [`Animation`] once, starting when inserted.
+///
+/// Children of staggered animations are read when the animation starts.
+#[derive(Debug, Clone, Component)]
+pub struct Animator {
+    animation: Animation,
+    /// Invariant: sorted by `at`, `None` if not started.
+    schedule: Option<Vec<Scheduled>>,
+    duration: f32,
+    elapsed: f32,
+    /// Steps started on this frame.
+    fired: (usize, usize),
+}
+
+impl Animator {
+    pub fn new(animation: Animation) -> Self {
+        Self {
+            animation,
+            schedule: None,
+            duration: 0.0,
+            elapsed: 0.0,
+            fired: (0, 0),
+        }
+    }
+
+    pub fn animation(&self) -> &Animation {
+        &self.animation
+    }
+
+    /// Time elapsed in seconds.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Returns true if all steps have started and ended.
+    pub fn is_finished(&self) -> bool {
+        self.schedule.is_some() && self.elapsed >= self.duration
+    }
+
+    /// Play the animation again from the start.
+    pub fn restart(&mut self) {
+        self.schedule = None;
+    }
+
+    /// Replace and play an animation.
+    pub fn play(&mut self, animation: Animation) {
+        self.animation = animation;
+        self.schedule = None;
+    }
+
+    fn fired(&self) -> &[Scheduled] {
+        match &self.schedule {
+            Some(schedule) => &schedule[self.fired.0..self.fired.1],
+            None => &[],
+        }
+    }
+
+    /// Advance animators and find steps that start on this frame.
+    pub fn update_animator(
+        clock: AnimationClock,
+        children: Query<&Children>,
+        mut query: Query<(Entity, &mut Animator)>
+    ) {
+        for (entity, mut animator) in query.iter_mut() {
+            let delta = clock.delta(entity);
+            if animator.is_finished() && animator.fired.0 == animator.fired.1 {
+                continue;
+            }
+            let animator = animator.as_mut();
+            let schedule = match &mut animator.schedule {
+                Some(schedule) => {
+                    animator.elapsed += delta;
+                    schedule
+                },
+                None => {
+                    let mut schedule = Vec::new();
+                    animator.duration = animator.animation.schedule(entity, 0.0, &children, &mut schedule);
+                    schedule.sort_by(|a, b| a.at.total_cmp(&b.at));
+                    animator.elapsed = 0.0;
+                    animator.fired = (0, 0);
+                    animator.schedule.insert(schedule)
+                },
+            };
+            let end = schedule.partition_point(|x| x.at <= animator.elapsed);
+            animator.fired = (animator.fired.1, end);
+        }
+    }
+}
+
+/// Start steps of [`Animator`]s on their `Interpolate<T>`.
+pub fn animation_system<T: Interpolation>(
+    animators: Query<&Animator>,
+    mut query: Query<&mut Interpolate<T>>
+) {
+    let id = TypeId::of::<T>();
+    for animator in animators.iter() {
+        for scheduled in animator.fired().iter().filter(|x| x.id == id) {
+            let Ok(mut interpolate) = query.get_mut(scheduled.entity) else {continue};
+            let Some(step) = scheduled.step.downcast_ref::<Step<T>>() else {continue};
+            interpolate.interpolate_with(step.target, step.curve, step.time);
+        }
+    }
+}
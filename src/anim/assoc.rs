@@ -3,15 +3,16 @@ use bevy::ecs::{component::Component, system::Query};
 use bevy::sprite::TextureAtlas;
 use bevy::ecs::query::{QueryData, QueryFilter};
 use crate::Coloring;
-use crate::{Transform2D, Dimension, Opacity};
-use super::{Interpolation, Interpolate, Offset, Rotation, Scale, Index};
+use crate::layout::Container;
+use crate::{Transform2D, Dimension, Opacity, Size2};
+use super::{Interpolation, Interpolate, Offset, Rotation, Scale, Index, Margin, Padding};
 
 
 /// Associate a component with an interpolation.
-pub trait InterpolateAssociation {
+pub trait InterpolateAssociation: Send + Sync + 'static {
     type Component: Component;
     type Interpolation: Interpolation;
-    type Condition: QueryFilter;
+    type Condition: QueryFilter + 'static;
 
     fn set(component: &mut Self::Component, value: <Self::Interpolation as Interpolation>::FrontEnd);
     fn get(component: &Self::Component) -> <Self::Interpolation as Interpolation>::FrontEnd;
@@ -26,6 +27,73 @@ pub trait InterpolateAssociation {
 
 }
 
+/// Create [`Interpolation`] markers for fields of components and associate them.
+///
+/// Since tuples are foreign types, associations outside of this crate cannot use the
+/// `(Component, Interpolation)` form, so the marker is its own association.
+///
+/// Each marker has a `plugin()` function returning its [`InterpolationPlugin`](super::InterpolationPlugin),
+/// which must be added to the `App`, otherwise `Interpolate<Marker>` never updates the component.
+/// [`RegisterInterpolation::register_interpolation`](super::RegisterInterpolation::register_interpolation)
+/// can be used instead.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_rectray::interpolate_field;
+/// # #[derive(Clone, Copy)] pub struct Outline { width: f32 }
+/// # #[derive(Component)] pub struct Circle { radius: f32, outline: Outline }
+/// # let mut app = App::new();
+/// interpolate_field! {
+///     /// Marker for the radius of a `Circle`.
+///     pub Radius(f32): Circle { radius };
+///     pub OutlineWidth(f32): Circle { outline.width };
+/// }
+///
+/// app.add_plugins((Radius::plugin(), OutlineWidth::plugin()));
+/// ```
+///
+/// The field is interpolated directly, so its type must implement
+/// `Add`, `Mul<f32>`, `PartialEq`, `Debug` and `Copy`.
+#[macro_export]
+macro_rules! interpolate_field {
+    ($($(#[$($attr: tt)*])* $vis: vis $name: ident ($ty: ty): $comp: ty { $($field: ident).+ });* $(;)?) => {
+        $(
+            $(#[$($attr)*])*
+            #[derive(Debug)]
+            $vis enum $name {}
+
+            impl $name {
+                /// Plugin adding the systems of this interpolation.
+                #[allow(dead_code)]
+                $vis fn plugin() -> $crate::anim::InterpolationPlugin<$name> {
+                    $crate::anim::InterpolationPlugin::default()
+                }
+            }
+
+            impl $crate::anim::Interpolation for $name {
+                type FrontEnd = $ty;
+                type Data = $ty;
+                fn into_data(data: Self::FrontEnd) -> Self::Data { data }
+                fn into_front_end(data: Self::Data) -> Self::FrontEnd { data }
+            }
+
+            impl $crate::anim::InterpolateAssociation for $name {
+                type Component = $comp;
+                type Interpolation = $name;
+                type Condition = ();
+
+                fn set(component: &mut Self::Component, value: $ty) {
+                    component.$($field).+ = value;
+                }
+
+                fn get(component: &Self::Component) -> $ty {
+                    component.$($field).+
+                }
+            }
+        )*
+    };
+}
+
 impl InterpolateAssociation for (Transform2D, Offset) {
     type Component = Transform2D;
     type Interpolation = Offset;
@@ -131,6 +199,53 @@ impl InterpolateAssociation for (Coloring, Color) {
 }
 
 
+/// Obtain the raw value of a margin or padding, whose units are kept during interpolation.
+fn raw_same_units(size: &Size2, name: &str) -> Vec2 {
+    let (x, y) = size.units();
+    if x != y {
+        panic!("Cannot interpolate {} with mixed units {:?} and {:?}.", name, x, y);
+    }
+    size.raw()
+}
+
+/// Interpolates in the margin's own units, which are preserved.
+///
+/// # Panics
+///
+/// If the x and y units of the margin are different.
+impl InterpolateAssociation for (Container, Margin) {
+    type Component = Container;
+    type Interpolation = Margin;
+    type Condition = ();
+
+    fn set<'t>(component: &mut Self::Component, value: <Self::Interpolation as Interpolation>::FrontEnd) {
+        component.margin.edit_raw(|x| *x = value);
+    }
+
+    fn get(component: &Self::Component) -> <Self::Interpolation as Interpolation>::FrontEnd {
+        raw_same_units(&component.margin, "margin")
+    }
+}
+
+/// Interpolates in the padding's own units, which are preserved.
+///
+/// # Panics
+///
+/// If the x and y units of the padding are different.
+impl InterpolateAssociation for (Container, Padding) {
+    type Component = Container;
+    type Interpolation = Padding;
+    type Condition = ();
+
+    fn set<'t>(component: &mut Self::Component, value: <Self::Interpolation as Interpolation>::FrontEnd) {
+        component.padding.edit_raw(|x| *x = value);
+    }
+
+    fn get(component: &Self::Component) -> <Self::Interpolation as Interpolation>::FrontEnd {
+        raw_same_units(&component.padding, "padding")
+    }
+}
+
 
 /// Query for either setting a field or setting its associated interpolation.
 #[derive(Debug, QueryData)]
@@ -244,3 +359,41 @@ impl AttrReadOnlyItem<'_, Transform2D, Offset> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec2;
+    use crate::layout::{Container, LayoutRange, StackLayout};
+    use crate::{Size, Size2, SizeUnit};
+    use super::{InterpolateAssociation, Margin, Padding};
+
+    fn container(margin: Size2) -> Container {
+        Container {
+            layout: StackLayout::HSTACK.into(),
+            margin,
+            padding: margin,
+            range: LayoutRange::default(),
+            maximum: 0,
+        }
+    }
+
+    #[test]
+    fn margin_keeps_units() {
+        let mut container = container(Size2::em(1.0, 2.0));
+        assert_eq!(<(Container, Margin)>::get(&container), Vec2::new(1.0, 2.0));
+        <(Container, Margin)>::set(&mut container, Vec2::new(3.0, 4.0));
+        <(Container, Padding)>::set(&mut container, Vec2::new(5.0, 6.0));
+        assert_eq!(container.margin, Size2::em(3.0, 4.0));
+        assert_eq!(container.padding, Size2::em(5.0, 6.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "mixed units")]
+    fn margin_rejects_mixed_units() {
+        let container = container(Size2::new(
+            Size::new(SizeUnit::Pixels, 1.0),
+            Size::new(SizeUnit::Em, 1.0),
+        ));
+        <(Container, Margin)>::get(&container);
+    }
+}
@@ -1,15 +1,18 @@
 use std::fmt::Debug;
+use std::marker::PhantomData;
 use std::ops::{Add, Mul};
 use ref_cast::RefCast;
-use bevy::{render::color::Color, time::Time};
-use bevy::ecs::{component::Component, system::{Query, Res}};
+use bevy::render::color::Color;
+use bevy::ecs::{component::Component, entity::Entity, query::Has, system::{Commands, Query}};
+use bevy::hierarchy::DespawnRecursiveExt;
 use bevy::math::{Vec2, Vec4};
 use bevy_defer::{AsyncComponent, AsyncComponentDeref, AsyncResult};
+use bevy_defer::signals::{SignalId, SignalSender};
 use crate::{Opacity, Dimension};
 use interpolation::EaseFunction;
 use smallvec::SmallVec;
 
-use super::{Easing, Playback};
+use super::{AnimationClock, Easing, Playback, Spring};
 
 #[derive(Debug, Clone, Component)]
 #[component(storage="SparseSet")]
@@ -18,6 +21,7 @@ use super::{Easing, Playback};
 pub struct Interpolate<T: Interpolation>{
     /// Easing function of the tweener.
     curve: Easing,
+    default_curve: Easing,
     /// Interpolates through these keyframes.
     ///
     /// Invariant: this field must have at least 1 value.
@@ -27,6 +31,12 @@ pub struct Interpolate<T: Interpolation>{
     time: f32,
     default_time: f32,
     playback: Playback,
+    /// If set, ignore `curve` and `time` and move to the target with a spring.
+    spring: Option<Spring>,
+    /// Initial velocity of the spring, `None` if zero.
+    velocity: Option<T::Data>,
+    /// If set, [`InterpolationFinished`] has been sent for the current animation.
+    finished: bool,
 }
 
 pub trait IntoInterpolate<T: Interpolation> {
@@ -63,11 +73,15 @@ impl<T: Interpolation> Interpolate<T> {
     pub const fn const_new(curve: Easing, position: T::Data, time: f32) -> Self {
         Interpolate {
             curve,
+            default_curve: curve,
             time: 0.0,
             default_time: time,
             range: SmallVec::from_const([(position, 0.0)]),
             current: 0.0,
             playback: Playback::Once,
+            spring: None,
+            velocity: None,
+            finished: true,
         }
     }
 
@@ -75,59 +89,108 @@ impl<T: Interpolation> Interpolate<T> {
     pub fn new(curve: Easing, position: T::FrontEnd, time: f32) -> Self {
         Interpolate {
             curve,
+            default_curve: curve,
             time: 0.0,
             default_time: time,
             range: SmallVec::from_const([(T::into_data(position), 0.0)]),
             current: 0.0,
             playback: Playback::Once,
+            spring: None,
+            velocity: None,
+            finished: true,
         }
     }
 
     pub fn ease(curve: EaseFunction, position: T::FrontEnd, time: f32) -> Self {
         Interpolate {
             curve: Easing::Ease(curve),
+            default_curve: Easing::Ease(curve),
             time: 0.0,
             default_time: time,
             range: SmallVec::from_const([(T::into_data(position), 0.0)]),
             current: 0.0,
             playback: Playback::Once,
+            spring: None,
+            velocity: None,
+            finished: true,
+        }
+    }
+
+    /// Create an `Interpolate` that moves to its targets with a [`Spring`].
+    pub fn spring(spring: Spring, position: T::FrontEnd) -> Self {
+        Interpolate {
+            curve: Easing::Linear,
+            default_curve: Easing::Linear,
+            time: 0.0,
+            default_time: 0.0,
+            range: SmallVec::from_const([(T::into_data(position), 0.0)]),
+            current: 0.0,
+            playback: Playback::Once,
+            spring: Some(spring),
+            velocity: None,
+            finished: true,
         }
     }
 
     pub fn init(curve: Easing, positions: impl IntoInterpolate<T>, time: f32) -> Self {
         Interpolate {
             curve,
+            default_curve: curve,
             time,
             default_time: time,
             range: positions.into_interpolate(),
             current: 0.0,
             playback: Playback::Once,
+            spring: None,
+            velocity: None,
+            finished: false,
         }
     }
 
     pub fn looping(curve: Easing, positions: impl IntoInterpolate<T>, time: f32) -> Self {
         Interpolate {
             curve,
+            default_curve: curve,
             time,
             default_time: time,
             range: positions.into_interpolate(),
             current: 0.0,
             playback: Playback::Loop,
+            spring: None,
+            velocity: None,
+            finished: false,
         }
     }
 
     pub fn repeat(curve: Easing, positions: impl IntoInterpolate<T>, time: f32) -> Self {
         Interpolate {
             curve,
+            default_curve: curve,
             time,
             default_time: time,
             range: positions.into_interpolate(),
             current: 0.0,
             playback: Playback::Repeat,
+            spring: None,
+            velocity: None,
+            finished: false,
         }
     }
 
     fn get_data(&self) -> T::Data {
+        if let Some(spring) = self.spring {
+            let (source, target) = (self.range[0].0, self.range.last().unwrap().0);
+            if self.range.len() == 1 {
+                return target;
+            }
+            return match spring.response(self.current) {
+                Some((x0, v0, ..)) => match self.velocity {
+                    Some(velocity) => target + (source + target * -1.0) * x0 + velocity * v0,
+                    None => target + (source + target * -1.0) * x0,
+                },
+                None => target,
+            };
+        }
         if self.range.len() == 1 || self.time <= 0.0 || (self.playback.is_once() && self.current >= self.time) {
             return self.range.last().expect("Interpolate has no value, this is a bug.").0;
         }
@@ -159,6 +222,45 @@ impl<T: Interpolation> Interpolate<T> {
         T::into_front_end(self.get_data())
     }
 
+    /// Get the current velocity of a spring, `None` if zero or not a spring.
+    pub fn velocity(&self) -> Option<T::Data> {
+        let spring = self.spring?;
+        if self.range.len() == 1 {
+            return None;
+        }
+        let (source, target) = (self.range[0].0, self.range.last().unwrap().0);
+        let (.., dx0, dv0) = spring.response(self.current)?;
+        match self.velocity {
+            Some(velocity) => Some((source + target * -1.0) * dx0 + velocity * dv0),
+            None => Some((source + target * -1.0) * dx0),
+        }
+    }
+
+    /// Returns true if the target is reached, `Loop` and `Repeat` never finish.
+    pub fn is_finished(&self) -> bool {
+        if self.range.len() == 1 {
+            return true;
+        }
+        match self.spring {
+            Some(spring) => spring.response(self.current).is_none(),
+            None => self.playback.is_once() && self.current >= self.time,
+        }
+    }
+
+    /// Returns true if this is a spring.
+    pub fn is_spring(&self) -> bool {
+        self.spring.is_some()
+    }
+
+    /// Retarget the spring, keeping its current position and velocity.
+    fn spring_to(&mut self, to: T::Data) {
+        let velocity = self.velocity();
+        self.range = [(self.get_data(), 0.0), (to, 1.0)].into_iter().collect();
+        self.velocity = velocity;
+        self.current = 0.0;
+        self.finished = false;
+    }
+
     /// Get source of this interpolation
     pub fn source(&self) -> T::Data {
         self.range.first().expect("Interpolate has no value, this is a bug.").0
@@ -174,6 +276,8 @@ impl<T: Interpolation> Interpolate<T> {
         let pos = self.get_data();
         let result = self.target();
         self.range = SmallVec::from_const([(pos, 0.0)]);
+        self.velocity = None;
+        self.finished = true;
         result
     }
 
@@ -185,20 +289,29 @@ impl<T: Interpolation> Interpolate<T> {
     /// Set position and stop interpolation.
     pub fn set(&mut self, pos: T::FrontEnd) {
         self.range = SmallVec::from_const([(T::into_data(pos), 0.0)]);
+        self.velocity = None;
         self.current = 0.0;
+        self.finished = true;
         self.time = self.default_time;
+        self.curve = self.default_curve;
     }
 
     /// If `to` is the current target, ignore.
     /// If `to` is the current source, reverse.
     /// Otherwise interpolate to the target.
     pub fn interpolate_to(&mut self, to: T::FrontEnd) {
-        if self.range.len() > 1 && T::into_front_end(self.range[0].0) == to {
+        if self.spring.is_some() {
+            if self.target() != to {
+                self.spring_to(T::into_data(to));
+            }
+        } else if self.range.len() > 1 && T::into_front_end(self.range[0].0) == to {
             self.reverse()
         } else if self.target() != to {
             self.range = [(self.get_data(), 0.0), (T::into_data(to), 1.0)].into_iter().collect();
             self.current = 0.0;
+            self.finished = false;
             self.time = self.default_time;
+            self.curve = self.default_curve;
         }
     }
 
@@ -208,9 +321,14 @@ impl<T: Interpolation> Interpolate<T> {
 
     /// Reverse the current curve.
     pub fn reverse(&mut self) {
+        if self.spring.is_some() {
+            self.spring_to(self.source());
+            return;
+        }
         self.range.reverse();
         self.range.iter_mut().for_each(|(_, x)| *x = 1.0 - *x);
         self.current = (self.time - self.current).clamp(0.0, self.time);
+        self.finished = false;
     }
 
     /// If end of `range` is the current target, ignore.
@@ -220,6 +338,12 @@ impl<T: Interpolation> Interpolate<T> {
     /// Write directly if this behavior is not desired.
     pub fn interpolate(&mut self, range: impl IntoInterpolate<T>) {
         let mut range = range.into_interpolate();
+        if self.spring.is_some() {
+            if !opt_eq::<T>(self.range.last(), range.last()) {
+                self.spring_to(range.last().expect("Interpolate has no value, this is a bug.").0);
+            }
+            return;
+        }
         if self.range.len() > 1 && opt_eq::<T>(range.last(), self.range.first()) {
             self.reverse()
         } else if !opt_eq::<T>(self.range.last(), range.last()) {
@@ -231,13 +355,20 @@ impl<T: Interpolation> Interpolate<T> {
             }
             self.range = range;
             self.current = 0.0;
+            self.finished = false;
             self.time = self.default_time;
+            self.curve = self.default_curve;
         }
     }
 
     /// Interpolate to a target, overwriting default time,
+    /// a spring only uses the last value of `range`.
     pub fn interpolate_with_time(&mut self, range: impl IntoInterpolate<T>, time: f32) {
         let mut range = range.into_interpolate();
+        if self.spring.is_some() {
+            self.spring_to(range.last().expect("Interpolate has no value, this is a bug.").0);
+            return;
+        }
         let pos = self.get_data();
         if range[0].1 == 0.0 {
             range[0] = (pos, 0.0);
@@ -246,7 +377,23 @@ impl<T: Interpolation> Interpolate<T> {
         }
         self.range = range;
         self.current = 0.0;
+        self.finished = false;
+        self.time = time;
+        self.curve = self.default_curve;
+    }
+
+    /// Interpolate to a target, overwriting default easing and time,
+    /// which are ignored by a spring.
+    pub fn interpolate_with(&mut self, to: T::FrontEnd, curve: Easing, time: f32) {
+        if self.spring.is_some() {
+            self.spring_to(T::into_data(to));
+            return;
+        }
+        self.range = [(self.get_data(), 0.0), (T::into_data(to), 1.0)].into_iter().collect();
+        self.current = 0.0;
+        self.finished = false;
         self.time = time;
+        self.curve = curve;
     }
 }
 
@@ -296,14 +443,37 @@ pub trait Interpolation: Sized + 'static {
     fn into_data(data: Self::FrontEnd) -> Self::Data;
     fn into_front_end(data: Self::Data) -> Self::FrontEnd;
     fn update_interpolate(
-        time: Res<Time>,
-        mut query: Query<&mut Interpolate<Self>>
+        mut commands: Commands,
+        clock: AnimationClock,
+        mut query: Query<(Entity, &mut Interpolate<Self>, SignalSender<InterpolationFinished<Self>>, Has<DespawnOnFinish>)>
     ) {
-        let delta = time.delta_seconds();
-        query.iter_mut().for_each(move |mut x| x.update(delta))
+        for (entity, mut interpolate, signal, despawn) in query.iter_mut() {
+            interpolate.update(clock.delta(entity));
+            if !interpolate.finished && interpolate.is_finished() {
+                interpolate.finished = true;
+                signal.send(());
+                if despawn {
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+        }
     }
 }
 
+/// Signal for an [`Interpolate<T>`] reaching its target.
+///
+/// Not sent for `Loop` and `Repeat`, or when the value is changed without interpolating.
+pub struct InterpolationFinished<T: Interpolation>(PhantomData<fn() -> T>);
+
+impl<T: Interpolation> SignalId for InterpolationFinished<T> {
+    type Data = ();
+}
+
+/// Despawns an entity and its descendants when any [`Interpolate`] on it finishes,
+/// use this for exit animations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Component)]
+pub struct DespawnOnFinish;
+
 /// Marker for offset.
 #[derive(Debug)]
 pub enum Offset{}
@@ -403,6 +573,17 @@ impl<T: Interpolation> AsyncInterpolate<'_, T> {
     pub async fn interpolate_to(&self, to: T::FrontEnd) -> AsyncResult<()> {
         self.0.set(move |x| x.interpolate_to(to)).await
     }
+
+    /// Wait until the target is reached.
+    pub async fn wait(&self) -> AsyncResult<()> {
+        self.0.watch(|x| x.is_finished().then_some(())).await
+    }
+
+    /// Interpolate to a target and wait until it is reached.
+    pub async fn interpolate_to_and_wait(&self, to: T::FrontEnd) -> AsyncResult<()> {
+        self.interpolate_to(to).await?;
+        self.wait().await
+    }
 }
 
 impl<T: Interpolation<FrontEnd = Vec2>> AsyncInterpolate<'_, T> {
@@ -43,6 +43,10 @@
 //! * Linear
 //! * [Ease Functions](EaseFunction)
 //! * Cubic Bézier `[f32; 4]`
+//! * Spring `spring(stiffness, damping)` or `spring(stiffness, damping, mass)`
+//!
+//! Replaces both time and easing, only available in the `default` mode.
+//! A [`Spring`] keeps its velocity when retargeted.
 //!
 //! ## Value
 //!
@@ -57,9 +61,71 @@
 //! * If target is the same, ignore.
 //! * If target is the source of current animation, reverse.
 //! * Otherwise interpolate to the target.
+//!
+//! # Custom Fields
+//!
+//! Use [`interpolate_field!`](crate::interpolate_field) to animate fields of your own components,
+//! then add their systems with the generated `Marker::plugin()`
+//! or [`RegisterInterpolation::register_interpolation`].
+//!
+//! # Completion
+//!
+//! [`InterpolationFinished<T>`] is sent when an `Interpolate<T>` reaches its target,
+//! and [`DespawnOnFinish`] despawns the entity afterwards, for exit animations.
+//! In async systems, use `interpolate_to_and_wait` or `wait`.
+//!
+//! # Time
+//!
+//! Animations run in `Update` using virtual time by default,
+//! use [`AnimationTime`] or [`AnimationTimeControl`] on a subtree
+//! to scale, pause or switch to real time, e.g. for pause menus.
+//!
+//! # Timeline
+//!
+//! For animations with more than two points on multiple fields,
+//! use a [`Timeline`] with a [`Track`] of keyframes for each field.
+//! ```
+//! # use bevy::math::Vec2;
+//! # use bevy_rectray::dsl::prelude::*;
+//! # let _ =
+//! Timeline::new()
+//!     .track(Track::<Opacity>::new()
+//!         .key(0.0, 0.0, Easing::Linear)
+//!         .key(0.5, 1.0, Easing::Linear))
+//!     .track(Track::<Offset>::new()
+//!         .key(0.0, Vec2::new(0.0, -40.0), Easing::Ease(EaseFunction::CubicOut))
+//!         .key(1.0, Vec2::ZERO, Easing::Linear))
+//!     .marker(1.0, "entered")
+//! # ;
+//! ```
+//!
+//! # Animation
+//!
+//! To chain multiple `Interpolate`s, use an [`Animator`] with an [`Animation`],
+//! which can be combined in sequence, in parallel, with delays, repeated
+//! or staggered across children.
+//! ```
+//! # use bevy_rectray::dsl::prelude::*;
+//! # use bevy_rectray::transition;
+//! # let _ =
+//! transition!(
+//!     Opacity 0.3 Linear default 0.0;
+//!     Scale 0.2 Linear default [1, 1];
+//!     animate sequence(
+//!         Opacity 0.3 Linear to 1.0,
+//!         delay 0.2,
+//!         repeat 2 (Scale 0.2 CubicOut to [1.2, 1.2], Scale 0.2 CubicIn to [1, 1]),
+//!     );
+//! )
+//! # ;
+//! ```
 
-use bevy::{app::{FixedUpdate, Plugin, Update}, ecs::query::QueryData, render::color::Color, sprite::TextureAtlas};
-use bevy::ecs::schedule::{SystemSet, IntoSystemConfigs, IntoSystemSetConfigs};
+use std::any::TypeId;
+use std::marker::PhantomData;
+use bevy::{app::{App, Plugin, Update}, ecs::query::QueryData, render::color::Color, sprite::TextureAtlas};
+use bevy::ecs::system::Resource;
+use bevy::utils::HashSet;
+use bevy::ecs::schedule::{SystemSet, IntoSystemConfigs, IntoSystemSetConfigs, apply_deferred};
 
 use ::interpolation::Ease;
 /// Enum for easing functions.
@@ -68,15 +134,24 @@ mod interpolation;
 pub use interpolation::{
     Interpolate, Interpolation, 
     Offset, Rotation, Scale, Index, Padding, Margin, 
-    AsyncInterpolate
+    AsyncInterpolate, InterpolationFinished, DespawnOnFinish
 };
 mod assoc;
 pub use assoc::{Attr, InterpolateAssociation};
 mod fgsm;
 pub use fgsm::{Fgsm, FgsmPairing, ComponentFgsm};
+mod time;
+pub use time::{AnimationTime, AnimationTimeControl, AnimationClock, EffectiveAnimationTime, TimeSource, propagate_animation_time};
+mod spring;
+pub use spring::Spring;
+mod timeline;
+pub use timeline::{Timeline, Track, TimelineMarker, timeline_system};
+mod animation;
+pub use animation::{Animation, Animator, animation_system};
 
 
 use crate::{Coloring, Dimension, Opacity, Transform2D};
+use crate::layout::Container;
 
 /// A easing function.
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
@@ -147,29 +222,83 @@ impl VisibilityToggleItem<'_> {
 pub(crate) struct AnimationPlugin;
 
 impl Plugin for AnimationPlugin {
-    fn build(&self, app: &mut bevy::prelude::App) {
+    fn build(&self, app: &mut App) {
         app
+            .init_resource::<AnimationTime>()
+            .init_resource::<RegisteredInterpolations>()
             .configure_sets(Update, InterpolationSet)
             .configure_sets(Update, InterpolationUpdateSet.after(InterpolationSet))
-            .add_systems(FixedUpdate, (
-                <(Transform2D, Offset)>::system,
-                <(Transform2D, Rotation)>::system,
-                <(Transform2D, Offset)>::system,
-                <(Transform2D, Scale)>::system,
-                <(Dimension, Dimension)>::system,
-                <(Coloring, Color)>::system,
-                <(Opacity, Opacity)>::system,
-                <(TextureAtlas, Index)>::system,
-            ).in_set(InterpolationSet))
-            .add_systems(FixedUpdate, (
-                Offset::update_interpolate,
-                Rotation::update_interpolate,
-                Scale::update_interpolate,
-                Dimension::update_interpolate,
-                Color::update_interpolate,
-                Opacity::update_interpolate,
-                Index::update_interpolate,
-            ).in_set(InterpolationUpdateSet))
+            .add_systems(Update, (propagate_animation_time, apply_deferred).chain().before(InterpolationSet))
+            .add_systems(Update, Animator::update_animator.in_set(InterpolationSet))
+            .add_systems(Update, Timeline::update_timeline.in_set(InterpolationUpdateSet))
+            .register_interpolation::<(Transform2D, Offset)>()
+            .register_interpolation::<(Transform2D, Rotation)>()
+            .register_interpolation::<(Transform2D, Scale)>()
+            .register_interpolation::<(Dimension, Dimension)>()
+            .register_interpolation::<(Coloring, Color)>()
+            .register_interpolation::<(Opacity, Opacity)>()
+            .register_interpolation::<(TextureAtlas, Index)>()
+            .register_interpolation::<(Container, Margin)>()
+            .register_interpolation::<(Container, Padding)>()
         ;
     }
 }
+
+/// Interpolations and associations with registered systems.
+#[derive(Debug, Default, Resource)]
+struct RegisteredInterpolations {
+    associations: HashSet<TypeId>,
+    interpolations: HashSet<TypeId>,
+}
+
+/// Extension for registering an [`InterpolateAssociation`] to the `App`.
+pub trait RegisterInterpolation {
+    /// Add systems of an [`InterpolateAssociation`] in [`InterpolationSet`]
+    /// and its [`Interpolation`] in [`InterpolationUpdateSet`],
+    /// including those for [`Timeline`] and [`Animator`].
+    ///
+    /// Systems of an `Interpolation` shared by multiple associations are only added once.
+    fn register_interpolation<T: InterpolateAssociation>(&mut self) -> &mut Self;
+}
+
+impl RegisterInterpolation for App {
+    fn register_interpolation<T: InterpolateAssociation>(&mut self) -> &mut Self {
+        let mut registered = self.world.get_resource_or_insert_with(RegisteredInterpolations::default);
+        let new_association = registered.associations.insert(TypeId::of::<T>());
+        let new_interpolation = registered.interpolations.insert(TypeId::of::<T::Interpolation>());
+        if new_association {
+            self.add_systems(Update, (
+                T::system,
+                timeline_system::<T>,
+            ).in_set(InterpolationSet));
+        }
+        if new_interpolation {
+            self.add_systems(Update, animation_system::<T::Interpolation>
+                .after(Animator::update_animator)
+                .in_set(InterpolationSet));
+            self.add_systems(Update, T::Interpolation::update_interpolate
+                .in_set(InterpolationUpdateSet));
+        }
+        self
+    }
+}
+
+/// A plugin that registers an [`InterpolateAssociation`], see [`RegisterInterpolation`].
+pub struct InterpolationPlugin<T: InterpolateAssociation>(PhantomData<T>);
+
+impl<T: InterpolateAssociation> Default for InterpolationPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: InterpolateAssociation> Plugin for InterpolationPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.register_interpolation::<T>();
+    }
+
+    /// Registration is idempotent, so adding this more than once is allowed.
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,166 @@
+/// A damped spring, used by [`Interpolate`](super::Interpolate) in place of easing and time.
+///
+/// The motion is solved analytically so it does not depend on frame rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+    pub mass: f32,
+    /// The spring settles on the target when both displacement and velocity
+    /// fall below this fraction of their initial values, by default `0.001`.
+    pub threshold: f32,
+}
+
+/// Damping ratios this close to `1.0` use the critically damped solution,
+/// the other solutions are numerically unstable near it.
+const CRITICAL_TOLERANCE: f32 = 1e-3;
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self::new(170.0, 26.0)
+    }
+}
+
+impl Spring {
+    pub const fn new(stiffness: f32, damping: f32) -> Self {
+        Self {
+            stiffness,
+            damping,
+            mass: 1.0,
+            threshold: 0.001,
+        }
+    }
+
+    pub const fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    pub const fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Returns responses `(x0, v0, dx0, dv0)` at time `t`,
+    /// for a unit initial displacement `x0` and a unit initial velocity `v0`,
+    /// and their derivatives, or `None` if settled.
+    pub fn response(&self, t: f32) -> Option<(f32, f32, f32, f32)> {
+        let mass = self.mass.max(f32::EPSILON);
+        let omega = (self.stiffness.max(f32::EPSILON) / mass).sqrt();
+        let zeta = self.damping.max(0.0) / (2.0 * (self.stiffness.max(f32::EPSILON) * mass).sqrt());
+        let result = if (zeta - 1.0).abs() <= CRITICAL_TOLERANCE {
+            let e = (-omega * t).exp();
+            (
+                e * (1.0 + omega * t),
+                e * t,
+                -e * omega * omega * t,
+                e * (1.0 - omega * t),
+            )
+        } else if zeta < 1.0 {
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            let e = (-zeta * omega * t).exp();
+            let (sin, cos) = (omega_d * t).sin_cos();
+            (
+                e * (cos + zeta * omega / omega_d * sin),
+                e * sin / omega_d,
+                -e * omega * omega / omega_d * sin,
+                e * (cos - zeta * omega / omega_d * sin),
+            )
+        } else {
+            let root = (zeta * zeta - 1.0).sqrt();
+            let r1 = -omega * (zeta - root);
+            let r2 = -omega * (zeta + root);
+            let (e1, e2) = ((r1 * t).exp(), (r2 * t).exp());
+            (
+                (r2 * e1 - r1 * e2) / (r2 - r1),
+                (e1 - e2) / (r1 - r2),
+                r1 * r2 * (e1 - e2) / (r2 - r1),
+                (r1 * e1 - r2 * e2) / (r1 - r2),
+            )
+        };
+        let (x0, v0, dx0, dv0) = result;
+        let settled = x0.abs() < self.threshold
+            && (v0 * omega).abs() < self.threshold
+            && (dx0 / omega).abs() < self.threshold
+            && dv0.abs() < self.threshold;
+        if settled {
+            None
+        } else {
+            Some(result)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::anim::{Interpolate, Rotation};
+    use super::Spring;
+
+    /// Integrate `m x'' + c x' + k x = 0` with RK4, returns `(x, v)` at `t`.
+    fn integrate(spring: Spring, x: f32, v: f32, t: f32) -> (f32, f32) {
+        let (k, c, m) = (spring.stiffness as f64, spring.damping as f64, spring.mass as f64);
+        let f = |x: f64, v: f64| (v, -(k * x + c * v) / m);
+        let steps = 100000;
+        let h = t as f64 / steps as f64;
+        let (mut x, mut v) = (x as f64, v as f64);
+        for _ in 0..steps {
+            let (a1, b1) = f(x, v);
+            let (a2, b2) = f(x + a1 * h / 2.0, v + b1 * h / 2.0);
+            let (a3, b3) = f(x + a2 * h / 2.0, v + b2 * h / 2.0);
+            let (a4, b4) = f(x + a3 * h, v + b3 * h);
+            x += (a1 + 2.0 * a2 + 2.0 * a3 + a4) * h / 6.0;
+            v += (b1 + 2.0 * b2 + 2.0 * b3 + b4) * h / 6.0;
+        }
+        (x as f32, v as f32)
+    }
+
+    fn assert_response(spring: Spring, tolerance: f32) {
+        let (x0, v0, dx0, dv0) = spring.response(0.0).unwrap();
+        assert!((x0 - 1.0).abs() < 1e-5 && v0.abs() < 1e-5);
+        assert!(dx0.abs() < 1e-5 && (dv0 - 1.0).abs() < 1e-5);
+        for t in [0.05, 0.1, 0.2, 0.4] {
+            let (x0, v0, dx0, dv0) = spring.response(t).unwrap();
+            let (x, dx) = integrate(spring, 1.0, 0.0, t);
+            let (v, dv) = integrate(spring, 0.0, 1.0, t);
+            assert!((x0 - x).abs() < tolerance, "{t}: {x0} != {x}");
+            assert!((dx0 - dx).abs() < tolerance * dx.abs().max(1.0), "{t}: {dx0} != {dx}");
+            assert!((v0 - v).abs() < tolerance, "{t}: {v0} != {v}");
+            assert!((dv0 - dv).abs() < tolerance, "{t}: {dv0} != {dv}");
+        }
+    }
+
+    #[test]
+    fn underdamped() {
+        assert_response(Spring::new(170.0, 10.0), 1e-4);
+    }
+
+    #[test]
+    fn critically_damped() {
+        assert_response(Spring::new(100.0, 20.0), 1e-4);
+        // Within the tolerance of critical damping.
+        assert_response(Spring::new(100.0, 20.01), 1e-3);
+        assert_response(Spring::new(100.0, 19.99), 1e-3);
+    }
+
+    #[test]
+    fn overdamped() {
+        assert_response(Spring::new(100.0, 50.0).with_mass(2.0), 1e-4);
+    }
+
+    #[test]
+    fn settles() {
+        assert!(Spring::default().response(10.0).is_none());
+    }
+
+    #[test]
+    fn interpolate_to_keeps_velocity() {
+        let mut interpolate = Interpolate::<Rotation>::spring(Spring::default(), 0.0);
+        interpolate.interpolate_to(1.0);
+        interpolate.update(0.05);
+        let (position, velocity) = (interpolate.get(), interpolate.velocity().unwrap());
+        assert!(velocity > 0.0);
+        interpolate.interpolate_to(-1.0);
+        assert!((interpolate.get() - position).abs() < 1e-5);
+        assert!((interpolate.velocity().unwrap() - velocity).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,184 @@
+use bevy::ecs::{component::Component, entity::{Entity, EntityHashSet}, query::With};
+use bevy::ecs::system::{Commands, Local, Query, Res, Resource, SystemParam};
+use bevy::hierarchy::{Children, HierarchyQueryExt, Parent};
+use bevy::time::{Real, Time};
+
+/// Source of time for animations.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TimeSource {
+    /// `Time<Virtual>`, scaled and paused with game time.
+    #[default]
+    Virtual,
+    /// `Time<Real>`, unaffected by pausing game time, for pause menus.
+    Real,
+    /// [`AnimationTime::custom_delta`], written by the user each frame.
+    Custom,
+}
+
+/// Global time control for [`Interpolate`](super::Interpolate),
+/// [`Timeline`](super::Timeline) and [`Animator`](super::Animator).
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+pub struct AnimationTime {
+    pub source: TimeSource,
+    /// Multiplier of time.
+    pub scale: f32,
+    pub paused: bool,
+    /// Delta time in seconds used by [`TimeSource::Custom`] on this frame.
+    pub custom_delta: f32,
+}
+
+impl Default for AnimationTime {
+    fn default() -> Self {
+        Self {
+            source: TimeSource::Virtual,
+            scale: 1.0,
+            paused: false,
+            custom_delta: 0.0,
+        }
+    }
+}
+
+/// Time control for animations of an entity and its descendants.
+///
+/// Scales multiply and pauses apply down the hierarchy,
+/// `source` overrides that of ancestors and [`AnimationTime`].
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct AnimationTimeControl {
+    pub source: Option<TimeSource>,
+    /// Multiplier of time.
+    pub scale: f32,
+    pub paused: bool,
+}
+
+impl Default for AnimationTimeControl {
+    fn default() -> Self {
+        Self {
+            source: None,
+            scale: 1.0,
+            paused: false,
+        }
+    }
+}
+
+impl AnimationTimeControl {
+    pub fn new(source: TimeSource) -> Self {
+        Self {
+            source: Some(source),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn paused(mut self) -> Self {
+        self.paused = true;
+        self
+    }
+}
+
+/// Effective [`AnimationTimeControl`] of an entity, combined with those of its ancestors.
+///
+/// Propagated once per frame by [`propagate_animation_time`]
+/// to entities with an `AnimationTimeControl` on themselves or an ancestor.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct EffectiveAnimationTime {
+    pub source: Option<TimeSource>,
+    pub scale: f32,
+    pub paused: bool,
+}
+
+impl EffectiveAnimationTime {
+    fn apply(self, control: Option<&AnimationTimeControl>) -> Self {
+        match control {
+            Some(control) => Self {
+                source: control.source.or(self.source),
+                scale: self.scale * control.scale,
+                paused: self.paused || control.paused,
+            },
+            None => self,
+        }
+    }
+}
+
+impl Default for EffectiveAnimationTime {
+    fn default() -> Self {
+        Self {
+            source: None,
+            scale: 1.0,
+            paused: false,
+        }
+    }
+}
+
+/// Propagate [`AnimationTimeControl`]s down the hierarchy into [`EffectiveAnimationTime`].
+pub fn propagate_animation_time(
+    mut commands: Commands,
+    mut visited: Local<EntityHashSet>,
+    controls: Query<(Entity, &AnimationTimeControl)>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
+    mut effective: Query<(Entity, &mut EffectiveAnimationTime)>,
+    has_control: Query<(), With<AnimationTimeControl>>,
+) {
+    visited.clear();
+    let mut stack = Vec::new();
+    for (entity, control) in controls.iter() {
+        // Only start from the topmost controls, the rest are visited from there.
+        if parents.iter_ancestors(entity).any(|x| has_control.contains(x)) {
+            continue;
+        }
+        stack.push((entity, EffectiveAnimationTime::default().apply(Some(control))));
+        while let Some((entity, time)) = stack.pop() {
+            visited.insert(entity);
+            match effective.get_mut(entity) {
+                Ok((_, mut current)) => {
+                    if *current != time {
+                        *current = time;
+                    }
+                },
+                Err(_) => {
+                    commands.entity(entity).insert(time);
+                },
+            }
+            for child in children.get(entity).into_iter().flatten() {
+                stack.push((*child, time.apply(controls.get(*child).ok().map(|(_, x)| x))));
+            }
+        }
+    }
+    for (entity, _) in effective.iter() {
+        if !visited.contains(&entity) {
+            commands.entity(entity).remove::<EffectiveAnimationTime>();
+        }
+    }
+}
+
+/// Query for the animation delta time of entities.
+///
+/// Reads [`EffectiveAnimationTime`], propagated before [`InterpolationSet`](super::InterpolationSet).
+#[derive(SystemParam)]
+pub struct AnimationClock<'w, 's> {
+    time: Res<'w, Time>,
+    real: Res<'w, Time<Real>>,
+    global: Option<Res<'w, AnimationTime>>,
+    effective: Query<'w, 's, &'static EffectiveAnimationTime>,
+}
+
+impl AnimationClock<'_, '_> {
+    /// Delta time in seconds of an entity on this frame.
+    pub fn delta(&self, entity: Entity) -> f32 {
+        let local = self.effective.get(entity).copied().unwrap_or_default();
+        let global = self.global.as_deref().copied().unwrap_or_default();
+        if local.paused || global.paused {
+            return 0.0;
+        }
+        let delta = match local.source.unwrap_or(global.source) {
+            TimeSource::Virtual => self.time.delta_seconds(),
+            TimeSource::Real => self.real.delta_seconds(),
+            TimeSource::Custom => global.custom_delta,
+        };
+        delta * local.scale * global.scale
+    }
+}
@@ -0,0 +1,383 @@
+use std::any::{Any, TypeId};
+use std::fmt::Debug;
+use std::sync::Arc;
+use bevy::ecs::{component::Component, entity::Entity, query::Changed, system::Query};
+use bevy::utils::HashMap;
+use bevy_defer::signals::{SignalId, SignalSender};
+
+use super::{AnimationClock, Easing, Interpolate, InterpolateAssociation, Interpolation, Playback};
+
+/// Keyframes of a single [`Interpolation`] target in a [`Timeline`].
+///
+/// The easing of a keyframe applies to the segment starting at that keyframe.
+pub struct Track<T: Interpolation> {
+    /// Invariant: sorted by time.
+    keyframes: Vec<(f32, T::Data, Easing)>,
+}
+
+impl<T: Interpolation> Debug for Track<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Track").field("keyframes", &self.keyframes).finish()
+    }
+}
+
+impl<T: Interpolation> Clone for Track<T> {
+    fn clone(&self) -> Self {
+        Self { keyframes: self.keyframes.clone() }
+    }
+}
+
+impl<T: Interpolation> Default for Track<T> {
+    fn default() -> Self {
+        Self { keyframes: Vec::new() }
+    }
+}
+
+impl<T: Interpolation> Track<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a keyframe at `time` in seconds, with the easing of the segment after it.
+    pub fn key(mut self, time: f32, value: T::FrontEnd, easing: Easing) -> Self {
+        let index = self.keyframes.partition_point(|(t, ..)| *t <= time);
+        self.keyframes.insert(index, (time, T::into_data(value), easing));
+        self
+    }
+
+    /// Time of the last keyframe.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map(|(t, ..)| *t).unwrap_or(0.0)
+    }
+
+    /// Obtain the value at `time`, `None` if the track is empty.
+    pub fn sample(&self, time: f32) -> Option<T::FrontEnd> {
+        let index = self.keyframes.partition_point(|(t, ..)| *t <= time);
+        let data = match (index.checked_sub(1).map(|i| self.keyframes[i]), self.keyframes.get(index).copied()) {
+            (None, None) => return None,
+            (None, Some((_, value, _))) | (Some((_, value, _)), None) => value,
+            (Some((t0, v0, easing)), Some((t1, v1, _))) => {
+                let p = easing.get((time - t0) / (t1 - t0));
+                v0 * (1.0 - p) + v1 * p
+            }
+        };
+        Some(T::into_front_end(data))
+    }
+}
+
+/// Signal for reaching a named marker of a [`Timeline`], sends the name of the marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineMarker {}
+
+impl SignalId for TimelineMarker {
+    type Data = String;
+}
+
+/// A keyframe animation driving multiple [`InterpolateAssociation`] targets on the same entity.
+///
+/// Tracks are shared, so cloning a timeline to play it on another entity is cheap.
+///
+/// If an [`Interpolate`](super::Interpolate) of the same target is present,
+/// it is moved without interpolating.
+///
+/// # Markers
+///
+/// Sends [`TimelineMarker`] when playback reaches or passes a named time, in either direction.
+/// After [`play`](Timeline::play) or [`seek`](Timeline::seek),
+/// a marker at the current position is sent as well.
+#[derive(Debug, Clone, Component)]
+pub struct Timeline {
+    tracks: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    /// Invariant: sorted by time.
+    markers: Vec<(f32, String)>,
+    duration: f32,
+    current: f32,
+    speed: f32,
+    reversed: bool,
+    playing: bool,
+    /// If set, send markers at the current position.
+    inclusive: bool,
+    playback: Playback,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            tracks: HashMap::new(),
+            markers: Vec::new(),
+            duration: 0.0,
+            current: 0.0,
+            speed: 1.0,
+            reversed: false,
+            playing: true,
+            inclusive: true,
+            playback: Playback::Once,
+        }
+    }
+}
+
+impl Timeline {
+    /// Create a timeline that starts playing immediately.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a track, replacing the existing track of the same target.
+    pub fn track<T: Interpolation>(mut self, track: Track<T>) -> Self {
+        self.duration = self.duration.max(track.duration());
+        self.tracks.insert(TypeId::of::<T>(), Arc::new(track));
+        self
+    }
+
+    /// Add a named marker at `time` in seconds.
+    pub fn marker(mut self, time: f32, name: impl Into<String>) -> Self {
+        self.duration = self.duration.max(time);
+        let index = self.markers.partition_point(|(t, _)| *t <= time);
+        self.markers.insert(index, (time, name.into()));
+        self
+    }
+
+    /// Set the playback mode, `Loop` plays back and forth.
+    pub fn with_playback(mut self, playback: Playback) -> Self {
+        self.playback = playback;
+        self
+    }
+
+    /// Set the playback speed.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Start paused.
+    pub fn paused(mut self) -> Self {
+        self.playing = false;
+        self
+    }
+
+    /// Obtain the track of an [`Interpolation`].
+    pub fn get_track<T: Interpolation>(&self) -> Option<&Track<T>> {
+        self.tracks.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Obtain the value of a track at the current time.
+    pub fn sample<T: Interpolation>(&self) -> Option<T::FrontEnd> {
+        self.get_track::<T>()?.sample(self.current)
+    }
+
+    /// Time of the last keyframe or marker.
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Current time in seconds.
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn is_reversed(&self) -> bool {
+        self.reversed
+    }
+
+    /// Returns true if a `Once` timeline is stopped at the end of its direction.
+    pub fn is_finished(&self) -> bool {
+        self.playback.is_once() && !self.playing && self.current == self.end()
+    }
+
+    /// Resume playback, restarts a finished `Once` timeline.
+    pub fn play(&mut self) {
+        if self.is_finished() {
+            self.current = self.start();
+        }
+        self.playing = true;
+        self.inclusive = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Move to `time` in seconds without sending markers in between.
+    pub fn seek(&mut self, time: f32) {
+        self.current = time.clamp(0.0, self.duration);
+        self.inclusive = true;
+    }
+
+    /// Reverse the direction of playback.
+    pub fn reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    /// Set the playback speed, a negative value plays backwards.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    fn forward(&self) -> bool {
+        self.reversed != (self.speed >= 0.0)
+    }
+
+    fn start(&self) -> f32 {
+        if self.forward() {0.0} else {self.duration}
+    }
+
+    fn end(&self) -> f32 {
+        if self.forward() {self.duration} else {0.0}
+    }
+
+    /// Advance by `delta` seconds, calling `marker` on every marker reached.
+    pub fn advance(&mut self, delta: f32, mut marker: impl FnMut(&str)) {
+        if !self.playing {
+            return;
+        }
+        let mut remaining = delta * self.speed.abs();
+        // Bounded in case the timeline is very short compared to `delta`.
+        for _ in 0..16 {
+            let forward = self.forward();
+            let end = self.end();
+            let target = if forward {
+                (self.current + remaining).min(end)
+            } else {
+                (self.current - remaining).max(end)
+            };
+            remaining -= (target - self.current).abs();
+            let (min, max) = if forward {(self.current, target)} else {(target, self.current)};
+            let inclusive = std::mem::take(&mut self.inclusive);
+            self.markers.iter()
+                .filter(|(t, _)| match (forward, inclusive) {
+                    (_, true) => min <= *t && *t <= max,
+                    (true, false) => min < *t && *t <= max,
+                    (false, false) => min <= *t && *t < max,
+                })
+                .for_each(|(_, name)| marker(name));
+            self.current = target;
+            if target != end {
+                return;
+            }
+            match self.playback {
+                Playback::Once => {
+                    self.playing = false;
+                    return;
+                },
+                _ if remaining <= 0.0 => return,
+                Playback::Loop => self.reversed = !self.reversed,
+                Playback::Repeat => {
+                    self.current = self.start();
+                    // The start is reached after wrapping.
+                    self.inclusive = true;
+                },
+            }
+            if self.duration <= 0.0 {
+                return;
+            }
+        }
+    }
+
+    /// Advance all timelines and send [`TimelineMarker`] signals.
+    pub fn update_timeline(
+        clock: AnimationClock,
+        mut query: Query<(Entity, &mut Timeline, SignalSender<TimelineMarker>)>
+    ) {
+        for (entity, mut timeline, sender) in query.iter_mut() {
+            if timeline.playing {
+                timeline.advance(clock.delta(entity), |name| sender.send(name.to_owned()));
+            }
+        }
+    }
+}
+
+/// Write the current value of a [`Timeline`]'s track to its associated component.
+pub fn timeline_system<T: InterpolateAssociation>(
+    mut query: Query<(&Timeline, &mut T::Component, Option<&mut Interpolate<T::Interpolation>>), (Changed<Timeline>, T::Condition)>
+) {
+    for (timeline, mut component, interpolate) in query.iter_mut() {
+        let Some(value) = timeline.sample::<T::Interpolation>() else {continue};
+        match interpolate {
+            Some(mut interpolate) => if interpolate.get() != value {
+                interpolate.set(value);
+                T::set(component.as_mut(), value);
+            },
+            None => if T::get(component.as_ref()) != value {
+                T::set(component.as_mut(), value);
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::anim::{Easing, Playback, Rotation};
+    use super::{Timeline, Track};
+
+    fn markers(timeline: &mut Timeline, delta: f32, count: usize) -> Vec<String> {
+        let mut result = Vec::new();
+        for _ in 0..count {
+            timeline.advance(delta, |name| result.push(name.to_owned()));
+        }
+        result
+    }
+
+    fn with_markers(playback: Playback) -> Timeline {
+        Timeline::new()
+            .marker(0.0, "start")
+            .marker(1.0, "end")
+            .with_playback(playback)
+    }
+
+    #[test]
+    fn track_sample() {
+        let track = Track::<Rotation>::new()
+            .key(1.0, 10.0, Easing::Linear)
+            .key(2.0, 20.0, Easing::Linear)
+            .key(0.0, 0.0, Easing::Linear);
+        assert_eq!(track.duration(), 2.0);
+        assert_eq!(track.sample(-1.0), Some(0.0));
+        assert_eq!(track.sample(0.0), Some(0.0));
+        assert_eq!(track.sample(0.5), Some(5.0));
+        assert_eq!(track.sample(1.0), Some(10.0));
+        assert_eq!(track.sample(1.25), Some(12.5));
+        assert_eq!(track.sample(3.0), Some(20.0));
+        assert_eq!(Track::<Rotation>::new().sample(0.0), None);
+    }
+
+    #[test]
+    fn markers_once() {
+        let mut timeline = with_markers(Playback::Once);
+        assert_eq!(markers(&mut timeline, 0.5, 4), ["start", "end"]);
+        assert!(timeline.is_finished());
+        timeline.play();
+        assert_eq!(markers(&mut timeline, 0.5, 2), ["start", "end"]);
+    }
+
+    #[test]
+    fn markers_loop() {
+        let mut timeline = with_markers(Playback::Loop);
+        assert_eq!(markers(&mut timeline, 0.5, 5), ["start", "end", "start"]);
+        assert_eq!(markers(&mut timeline, 0.5, 4), ["end", "start"]);
+    }
+
+    #[test]
+    fn markers_repeat() {
+        let mut timeline = with_markers(Playback::Repeat);
+        assert_eq!(markers(&mut timeline, 0.5, 5), ["start", "end", "start", "end", "start"]);
+        let mut timeline = with_markers(Playback::Repeat);
+        assert_eq!(markers(&mut timeline, 1.5, 2), ["start", "end", "start", "end", "start", "end"]);
+    }
+
+    #[test]
+    fn markers_negative_speed() {
+        let mut timeline = with_markers(Playback::Once).with_speed(-1.0);
+        timeline.seek(1.0);
+        assert_eq!(markers(&mut timeline, 0.5, 3), ["end", "start"]);
+        assert!(timeline.is_finished());
+        assert_eq!(timeline.current(), 0.0);
+    }
+}
@@ -0,0 +1,204 @@
+//! Widget trees loaded from RON or JSON assets.
+//!
+//! A [`UiAsset`] is a serializable [`WidgetNode`] tree, covering fields of
+//! [`FrameBuilder`](crate::dsl::builders::FrameBuilder),
+//! [`SpriteBuilder`](crate::dsl::builders::SpriteBuilder),
+//! [`RectangleBuilder`](crate::dsl::builders::RectangleBuilder),
+//! [`TextBuilder`](crate::dsl::builders::TextBuilder) and layouts.
+//! Files with extensions `.ui.ron` and `.ui.json` are loaded as [`UiAsset`]s.
+//!
+//! ```ron
+//! (
+//!     name: "menu",
+//!     layout: Some(VStack),
+//!     children: [
+//!         (
+//!             name: "title",
+//!             widget: Text(text: "Hello"),
+//!             color: Some(Rgba(red: 1.0, green: 0.84, blue: 0.0, alpha: 1.0)),
+//!         ),
+//!         (
+//!             name: "start",
+//!             widget: Sprite(sprite: "button.png"),
+//!             event: ["LeftClick"],
+//!             signals: [(id: "ButtonClick", name: "start_game")],
+//!             callbacks: ["make_button"],
+//!         ),
+//!     ]
+//! )
+//! ```
+//!
+//! Closures cannot be serialized, instead events and signals are bound by name:
+//!
+//! * `callbacks` are looked up in the [`UiRegistry`] and run on the spawned entity.
+//! * `signals` bind a [`SignalId`](bevy_defer::signals::SignalId) registered in the [`UiRegistry`]
+//!   to a named signal in the [`SignalPool`](crate::util::SignalPool),
+//!   obtainable by [`RCommands::signal`].
+//!
+//! Add [`UiAssetRoot`] to an entity to spawn the tree as its child once loaded.
+//!
+//! # Hot Reloading
+//!
+//! When a [`UiAsset`] is modified, for example by enabling `watch_for_changes`
+//! in bevy's `AssetPlugin`, spawned trees are updated in place.
+//!
+//! Nodes are identified by their names and paths from the root,
+//! entities of these nodes are kept and only components generated by the builders are updated,
+//! so states like `InputBox` text, `CheckButton`, `Interpolate` and `Scrolling` survive a reload.
+//! Fields of `Transform2D` are only updated if changed in the asset.
+//! Nodes whose widget kind, signals or callbacks changed are rebuilt with their children.
+
+use bevy::app::{Plugin, Update};
+use bevy::asset::io::Reader;
+use bevy::asset::{Asset, AssetApp, AssetEvent, AssetLoader, Assets, AsyncReadExt, Handle, LoadContext};
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::Entity;
+use bevy::ecs::event::EventReader;
+use bevy::ecs::system::{Query, Res};
+use bevy::hierarchy::BuildChildren;
+use bevy::reflect::TypePath;
+use bevy::utils::{BoxedFuture, HashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::util::RCommands;
+use reload::{SpawnedNode, spawn_tracked, reconcile};
+
+mod node;
+mod registry;
+mod reload;
+pub use node::{WidgetNode, WidgetKind, AnchorNode, LayoutNode, SignalNode, SignalRole, parse_event_flags};
+pub use registry::UiRegistry;
+
+/// A widget tree loaded from a RON or JSON file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Asset, TypePath)]
+#[serde(transparent)]
+pub struct UiAsset {
+    pub root: WidgetNode,
+}
+
+impl UiAsset {
+    /// Spawn the widget tree.
+    pub fn spawn(&self, commands: &mut RCommands, registry: &UiRegistry) -> Entity {
+        self.root.spawn(commands, registry)
+    }
+}
+
+/// Error when loading a [`UiAsset`].
+#[derive(Debug, thiserror::Error)]
+pub enum UiAssetError {
+    #[error("Could not read asset: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse RON: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Could not parse JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// [`AssetLoader`] for [`UiAsset`].
+#[derive(Debug, Default)]
+pub struct UiAssetLoader;
+
+impl AssetLoader for UiAssetLoader {
+    type Asset = UiAsset;
+    type Settings = ();
+    type Error = UiAssetError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let is_json = load_context.path().extension().is_some_and(|x| x == "json");
+            if is_json {
+                Ok(serde_json::from_slice(&bytes)?)
+            } else {
+                Ok(ron::de::from_bytes(&bytes)?)
+            }
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ui.ron", "ui.json"]
+    }
+}
+
+/// Spawns a [`UiAsset`] as a child of this entity once loaded,
+/// and updates the spawned tree when the asset is modified.
+#[derive(Debug, Clone, Component)]
+pub struct UiAssetRoot {
+    pub handle: Handle<UiAsset>,
+    tree: Option<WidgetNode>,
+    spawned: Option<SpawnedNode>,
+}
+
+impl UiAssetRoot {
+    pub fn new(handle: Handle<UiAsset>) -> Self {
+        Self { handle, tree: None, spawned: None }
+    }
+
+    /// Root of the spawned widget tree.
+    pub fn spawned(&self) -> Option<Entity> {
+        self.spawned.as_ref().map(|x| x.entity)
+    }
+
+    /// Find a spawned entity by a `/` separated path of names, excluding the root.
+    ///
+    /// Returns the root if `path` is empty.
+    pub fn entity_at(&self, path: &str) -> Option<Entity> {
+        let (Some(tree), Some(spawned)) = (&self.tree, &self.spawned) else {return None};
+        spawned.find(tree, path.split('/').filter(|x| !x.is_empty()))
+    }
+}
+
+pub(crate) fn spawn_ui_assets(
+    mut commands: RCommands,
+    registry: Res<UiRegistry>,
+    assets: Res<Assets<UiAsset>>,
+    mut events: EventReader<AssetEvent<UiAsset>>,
+    mut query: Query<(Entity, &mut UiAssetRoot)>,
+) {
+    let modified: HashSet<_> = events.read().filter_map(|event| match event {
+        AssetEvent::Modified { id } => Some(*id),
+        _ => None,
+    }).collect();
+    for (entity, mut root) in query.iter_mut() {
+        let Some(asset) = assets.get(&root.handle) else {continue};
+        let root = &mut *root;
+        match (root.tree.take(), root.spawned.take()) {
+            (Some(tree), Some(spawned)) if modified.contains(&root.handle.id()) => {
+                let previous = spawned.entity;
+                let spawned = reconcile(&tree, &asset.root, spawned, &mut commands, &registry);
+                if spawned.entity != previous {
+                    commands.entity(entity).add_child(spawned.entity);
+                }
+                root.tree = Some(asset.root.clone());
+                root.spawned = Some(spawned);
+            }
+            (Some(tree), Some(spawned)) => {
+                root.tree = Some(tree);
+                root.spawned = Some(spawned);
+            }
+            _ => {
+                let spawned = spawn_tracked(&asset.root, &mut commands, &registry);
+                commands.entity(entity).add_child(spawned.entity);
+                root.tree = Some(asset.root.clone());
+                root.spawned = Some(spawned);
+            }
+        }
+    }
+}
+
+pub(crate) struct UiAssetPlugin;
+
+impl Plugin for UiAssetPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_asset::<UiAsset>()
+            .register_asset_loader(UiAssetLoader)
+            .init_resource::<UiRegistry>()
+            .add_systems(Update, spawn_ui_assets);
+    }
+}
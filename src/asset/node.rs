@@ -0,0 +1,310 @@
+use bevy::ecs::entity::Entity;
+use bevy::hierarchy::BuildChildren;
+use bevy::log::warn;
+use bevy::math::Vec2;
+use bevy::render::color::Color;
+use bevy_defer::signals::Signals;
+use serde::{Deserialize, Serialize};
+
+use crate::dsl::builders::{FrameBuilder, RectangleBuilder, SpriteBuilder, TextBuilder};
+use crate::dsl::{OneOrTwo, ParentAnchor, Scale, SpacialConst};
+use crate::events::EventFlags;
+use crate::layout::{BoundsLayout, LayoutObject, ParagraphLayout, SpanLayout, StackLayout};
+use crate::util::{convert::IntoAsset, RCommands, Widget};
+use crate::{Anchor, DimensionType, FontSize, Hitbox, Opacity, Size2};
+
+use super::UiRegistry;
+
+/// An [`Anchor`] by name or by value.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AnchorNode {
+    Named(SpacialConst),
+    Custom(Vec2),
+}
+
+impl Default for AnchorNode {
+    fn default() -> Self {
+        AnchorNode::Named(SpacialConst::Center)
+    }
+}
+
+impl AnchorNode {
+    pub fn into_anchor(self) -> Anchor {
+        match self {
+            AnchorNode::Custom(v) => Anchor::new(v),
+            AnchorNode::Named(c) => match c {
+                SpacialConst::TopLeft => Anchor::TOP_LEFT,
+                SpacialConst::TopCenter | SpacialConst::Top => Anchor::TOP_CENTER,
+                SpacialConst::TopRight => Anchor::TOP_RIGHT,
+                SpacialConst::CenterLeft | SpacialConst::Left => Anchor::CENTER_LEFT,
+                SpacialConst::Center => Anchor::CENTER,
+                SpacialConst::CenterRight | SpacialConst::Right => Anchor::CENTER_RIGHT,
+                SpacialConst::BottomLeft => Anchor::BOTTOM_LEFT,
+                SpacialConst::BottomCenter | SpacialConst::Bottom => Anchor::BOTTOM_CENTER,
+                SpacialConst::BottomRight => Anchor::BOTTOM_RIGHT,
+                c => {
+                    warn!("{:?} is not an Anchor.", c);
+                    Anchor::CENTER
+                }
+            }
+        }
+    }
+}
+
+/// Serializable layouts of a [`WidgetNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LayoutNode {
+    HStack,
+    VStack,
+    HBox,
+    VBox,
+    Paragraph,
+    Padding,
+}
+
+impl LayoutNode {
+    pub fn into_layout(self) -> LayoutObject {
+        match self {
+            LayoutNode::HStack => StackLayout::HSTACK.into(),
+            LayoutNode::VStack => StackLayout::VSTACK.into(),
+            LayoutNode::HBox => SpanLayout::HBOX.into(),
+            LayoutNode::VBox => SpanLayout::VBOX.into(),
+            LayoutNode::Paragraph => ParagraphLayout::PARAGRAPH.into(),
+            LayoutNode::Padding => BoundsLayout::PADDING.into(),
+        }
+    }
+}
+
+/// Role of a signal bound by name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignalRole {
+    #[default]
+    Sender,
+    Receiver,
+}
+
+/// Binds a [`SignalId`](bevy_defer::signals::SignalId) registered in [`UiRegistry`]
+/// to a named signal in [`SignalPool`](crate::util::SignalPool).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignalNode {
+    /// Name of the `SignalId` in the registry.
+    pub id: String,
+    /// Name of the signal in the `SignalPool`.
+    pub name: String,
+    #[serde(default)]
+    pub role: SignalRole,
+}
+
+/// Widget specific fields of a [`WidgetNode`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum WidgetKind {
+    /// Corresponds to [`FrameBuilder`].
+    #[default]
+    Frame,
+    /// Corresponds to [`SpriteBuilder`].
+    Sprite {
+        sprite: String,
+        #[serde(default)]
+        size: Option<Vec2>,
+        #[serde(default)]
+        flip: [bool; 2],
+    },
+    /// Corresponds to [`RectangleBuilder`].
+    Rectangle {
+        #[serde(default)]
+        size: Option<Vec2>,
+    },
+    /// Corresponds to [`TextBuilder`].
+    Text {
+        text: String,
+        #[serde(default)]
+        font: Option<String>,
+        #[serde(default)]
+        wrap: bool,
+    },
+}
+
+/// Serializable description of a widget and its children.
+///
+/// Fields correspond to fields of the widget builders.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WidgetNode {
+    pub name: String,
+    pub widget: WidgetKind,
+    pub anchor: AnchorNode,
+    pub parent_anchor: Option<AnchorNode>,
+    pub center: Option<AnchorNode>,
+    pub offset: Size2,
+    pub rotation: f32,
+    pub scale: Option<Vec2>,
+    pub z: f32,
+    pub opacity: Option<f32>,
+    pub clipping: Option<bool>,
+    pub dimension: DimensionType,
+    pub font_size: FontSize,
+    pub color: Option<Color>,
+    /// Names of [`EventFlags`], like `LeftClick`.
+    pub event: Vec<String>,
+    pub hitbox: Option<Hitbox>,
+    pub layout: Option<LayoutNode>,
+    pub margin: Size2,
+    pub padding: Size2,
+    pub style: Option<String>,
+    /// Names of signals to bind.
+    pub signals: Vec<SignalNode>,
+    /// Names of callbacks in the [`UiRegistry`] to run on the spawned entity.
+    pub callbacks: Vec<String>,
+    pub children: Vec<WidgetNode>,
+}
+
+/// Parse the name of an [`EventFlags`].
+pub fn parse_event_flags(name: &str) -> Option<EventFlags> {
+    Some(match name {
+        "Idle" => EventFlags::Idle,
+        "Hover" => EventFlags::Hover,
+        "LeftDrag" => EventFlags::LeftDrag,
+        "LeftDown" => EventFlags::LeftDown,
+        "LeftPressed" => EventFlags::LeftPressed,
+        "LeftClick" => EventFlags::LeftClick,
+        "DoubleClick" => EventFlags::DoubleClick,
+        "MidDown" => EventFlags::MidDown,
+        "MidPressed" => EventFlags::MidPressed,
+        "MidClick" => EventFlags::MidClick,
+        "MidDrag" => EventFlags::MidDrag,
+        "RightDown" => EventFlags::RightDown,
+        "RightPressed" => EventFlags::RightPressed,
+        "RightClick" => EventFlags::RightClick,
+        "RightDrag" => EventFlags::RightDrag,
+        "Drop" => EventFlags::Drop,
+        "DragEnd" => EventFlags::DragEnd,
+        "ClickOutside" => EventFlags::ClickOutside,
+        "MouseWheel" => EventFlags::MouseWheel,
+        "AnyClick" => EventFlags::AnyClick,
+        "AnyDown" => EventFlags::AnyDown,
+        "AnyDrag" => EventFlags::AnyDrag,
+        "BlockAll" => EventFlags::BlockAll,
+        "All" => EventFlags::All,
+        _ => return None,
+    })
+}
+
+macro_rules! node_builder {
+    ($node: expr, $builder: ident {$($field: ident: $value: expr),* $(,)?}) => {
+        $builder {
+            name: $node.name.clone(),
+            anchor: $node.anchor.into_anchor(),
+            parent_anchor: ParentAnchor($node.parent_anchor.map(|x| x.into_anchor()).unwrap_or(Anchor::INHERIT)),
+            center: $node.center.map(|x| x.into_anchor()).unwrap_or_default(),
+            opacity: Opacity::new($node.opacity.unwrap_or(1.0)),
+            offset: $node.offset,
+            rotation: $node.rotation,
+            scale: Scale($node.scale.unwrap_or(Vec2::ONE)),
+            z: $node.z,
+            clipping: $node.clipping,
+            dimension: $node.dimension,
+            font_size: $node.font_size,
+            color: $node.color,
+            event: $node.event(),
+            hitbox: $node.hitbox,
+            layout: $node.layout.map(|x| x.into_layout()),
+            margin: OneOrTwo($node.margin),
+            padding: OneOrTwo($node.padding),
+            style: $node.style.clone(),
+            $($field: $value,)*
+            ..Default::default()
+        }
+    };
+}
+
+impl WidgetNode {
+    /// Obtain [`EventFlags`] from names.
+    pub fn event(&self) -> EventFlags {
+        self.event.iter().fold(EventFlags::default(), |acc, name| {
+            match parse_event_flags(name) {
+                Some(flag) => acc | flag,
+                None => {
+                    warn!("Unknown event {}.", name);
+                    acc
+                }
+            }
+        })
+    }
+
+    /// Returns true if fields other than `children` are equal.
+    pub fn eq_ignore_children(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.widget == other.widget
+            && self.anchor == other.anchor
+            && self.parent_anchor == other.parent_anchor
+            && self.center == other.center
+            && self.offset == other.offset
+            && self.rotation == other.rotation
+            && self.scale == other.scale
+            && self.z == other.z
+            && self.opacity == other.opacity
+            && self.clipping == other.clipping
+            && self.dimension == other.dimension
+            && self.font_size == other.font_size
+            && self.color == other.color
+            && self.event == other.event
+            && self.hitbox == other.hitbox
+            && self.layout == other.layout
+            && self.margin == other.margin
+            && self.padding == other.padding
+            && self.style == other.style
+            && self.signals == other.signals
+            && self.callbacks == other.callbacks
+    }
+
+    /// Spawn the widget of this node without signals, callbacks and children,
+    /// returns the entity and its container.
+    pub fn spawn_widget(&self, commands: &mut RCommands) -> (Entity, Entity) {
+        match &self.widget {
+            WidgetKind::Frame => node_builder!(self, FrameBuilder {}).spawn(commands),
+            WidgetKind::Sprite { sprite, size, flip } => node_builder!(self, SpriteBuilder {
+                sprite: IntoAsset::String(sprite.clone()),
+                size: *size,
+                flip: *flip,
+            }).spawn(commands),
+            WidgetKind::Rectangle { size } => node_builder!(self, RectangleBuilder {
+                size: *size,
+            }).spawn(commands),
+            WidgetKind::Text { text, font, wrap } => node_builder!(self, TextBuilder {
+                text: text.clone(),
+                font: match font {
+                    Some(font) => IntoAsset::String(font.clone()),
+                    None => IntoAsset::None,
+                },
+                wrap: *wrap,
+            }).spawn(commands),
+        }
+    }
+
+    /// Spawn this node without its children, returns the entity and its container.
+    pub fn spawn_node(&self, commands: &mut RCommands, registry: &UiRegistry) -> (Entity, Entity) {
+        let (entity, container) = self.spawn_widget(commands);
+        if !self.signals.is_empty() {
+            let mut signals = Signals::new();
+            for node in &self.signals {
+                registry.bind_signal(commands, &mut signals, node);
+            }
+            commands.entity(entity).insert(signals);
+        }
+        for name in &self.callbacks {
+            registry.run_callback(commands, entity, name);
+        }
+        (entity, container)
+    }
+
+    /// Spawn this node and its children.
+    pub fn spawn(&self, commands: &mut RCommands, registry: &UiRegistry) -> Entity {
+        let (entity, container) = self.spawn_node(commands, registry);
+        for child in &self.children {
+            let child = child.spawn(commands, registry);
+            commands.entity(container).add_child(child);
+        }
+        entity
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use bevy::ecs::entity::Entity;
+use bevy::ecs::system::Resource;
+use bevy::log::warn;
+use bevy::utils::HashMap;
+use bevy_defer::Object;
+use bevy_defer::signals::{SignalId, Signals, TypedSignal};
+
+use crate::util::RCommands;
+use crate::widgets::button::{ButtonClick, ToggleChange};
+use crate::widgets::constraints::{PositionFac, SharedPosition};
+use crate::widgets::inputbox::{TextChange, TextSubmit};
+
+use super::node::{SignalNode, SignalRole};
+
+type Callback = Arc<dyn Fn(&mut RCommands, Entity) + Send + Sync + 'static>;
+type SignalBinder = fn(&mut Signals, TypedSignal<Object>, SignalRole);
+
+fn bind<T: SignalId>(signals: &mut Signals, signal: TypedSignal<Object>, role: SignalRole) {
+    match role {
+        SignalRole::Sender => signals.add_sender::<T>(signal.of_type()),
+        SignalRole::Receiver => signals.add_receiver::<T>(signal.of_type()),
+    }
+}
+
+/// Rust callbacks and [`SignalId`]s that can be referenced by name in a [`UiAsset`](super::UiAsset).
+///
+/// `ButtonClick`, `ToggleChange`, `TextChange`, `TextSubmit`,
+/// `PositionFac` and `SharedPosition` are registered by default.
+#[derive(Resource, Clone)]
+pub struct UiRegistry {
+    callbacks: HashMap<String, Callback>,
+    signal_ids: HashMap<String, SignalBinder>,
+}
+
+impl std::fmt::Debug for UiRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UiRegistry")
+            .field("callbacks", &self.callbacks.keys().collect::<Vec<_>>())
+            .field("signal_ids", &self.signal_ids.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Default for UiRegistry {
+    fn default() -> Self {
+        let mut result = Self {
+            callbacks: HashMap::new(),
+            signal_ids: HashMap::new(),
+        };
+        result.register_signal_id::<ButtonClick>("ButtonClick");
+        result.register_signal_id::<ToggleChange>("ToggleChange");
+        result.register_signal_id::<TextChange>("TextChange");
+        result.register_signal_id::<TextSubmit>("TextSubmit");
+        result.register_signal_id::<PositionFac>("PositionFac");
+        result.register_signal_id::<SharedPosition>("SharedPosition");
+        result
+    }
+}
+
+impl UiRegistry {
+    /// Register a callback that runs on a spawned entity, usually to insert components.
+    pub fn register_callback(&mut self, name: impl Into<String>, callback: impl Fn(&mut RCommands, Entity) + Send + Sync + 'static) -> &mut Self {
+        self.callbacks.insert(name.into(), Arc::new(callback));
+        self
+    }
+
+    /// Register a [`SignalId`] by name.
+    pub fn register_signal_id<T: SignalId>(&mut self, name: impl Into<String>) -> &mut Self {
+        self.signal_ids.insert(name.into(), bind::<T>);
+        self
+    }
+
+    /// Run a named callback on an entity.
+    pub fn run_callback(&self, commands: &mut RCommands, entity: Entity, name: &str) {
+        match self.callbacks.get(name) {
+            Some(callback) => callback(commands, entity),
+            None => warn!("Callback {} is not registered.", name),
+        }
+    }
+
+    /// Bind a named signal from the [`SignalPool`](crate::util::SignalPool).
+    pub fn bind_signal(&self, commands: &RCommands, signals: &mut Signals, node: &SignalNode) {
+        match self.signal_ids.get(&node.id) {
+            Some(binder) => binder(signals, commands.signal::<Object, TypedSignal<Object>>(node.name.as_str()), node.role),
+            None => warn!("SignalId {} is not registered.", node.id),
+        }
+    }
+}
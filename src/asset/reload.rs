@@ -0,0 +1,356 @@
+use std::mem::discriminant;
+
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::Entity;
+use bevy::ecs::world::World;
+use bevy::hierarchy::{despawn_with_children_recursive, BuildChildren, BuildWorldChildren, Children};
+use bevy::core::Name;
+use bevy::math::Vec2;
+use bevy::asset::Handle;
+use bevy::render::texture::Image;
+use bevy::sprite::Sprite;
+use bevy::text::{Font, Text};
+use bevy::utils::HashMap;
+
+use crate::events::EventFlags;
+use crate::layout::Container;
+use crate::style::Styled;
+use crate::util::RCommands;
+use crate::{Clipping, Coloring, Dimension, Hitbox, Opacity, Size2, Transform2D};
+
+use super::{AnchorNode, UiRegistry, WidgetKind, WidgetNode};
+
+/// Entities of a spawned [`WidgetNode`] tree.
+#[derive(Debug, Clone)]
+pub(crate) struct SpawnedNode {
+    pub entity: Entity,
+    pub container: Entity,
+    pub children: Vec<SpawnedNode>,
+}
+
+impl SpawnedNode {
+    /// Find an entity by a path of names, starting from children of `node`.
+    pub fn find<'t>(&self, node: &WidgetNode, mut path: impl Iterator<Item = &'t str>) -> Option<Entity> {
+        let Some(name) = path.next() else {return Some(self.entity)};
+        node.children.iter().zip(self.children.iter())
+            .find(|(node, _)| node.name == name)
+            .and_then(|(node, spawned)| spawned.find(node, path))
+    }
+}
+
+/// Spawn a [`WidgetNode`] tree and record its entities.
+pub(crate) fn spawn_tracked(node: &WidgetNode, commands: &mut RCommands, registry: &UiRegistry) -> SpawnedNode {
+    let (entity, container) = node.spawn_node(commands, registry);
+    let children: Vec<_> = node.children.iter()
+        .map(|child| spawn_tracked(child, commands, registry))
+        .collect();
+    for child in &children {
+        commands.entity(container).add_child(child.entity);
+    }
+    SpawnedNode { entity, container, children }
+}
+
+/// Keys of siblings, as names and the number of previous siblings with the same name.
+fn sibling_keys(nodes: &[WidgetNode]) -> impl Iterator<Item = (&str, usize)> {
+    let mut counts = HashMap::<&str, usize>::new();
+    nodes.iter().map(move |node| {
+        let count = counts.entry(node.name.as_str()).or_default();
+        *count += 1;
+        (node.name.as_str(), *count - 1)
+    })
+}
+
+/// Copy or remove `T` if the fields of the node that produce it changed.
+///
+/// `T` is only removed if the old node produced it.
+fn patch<T: Component + Clone>(world: &mut World, from: Entity, to: Entity, changed: bool) {
+    if !changed {
+        return;
+    }
+    let item = world.get::<T>(from).cloned();
+    let Some(mut entity) = world.get_entity_mut(to) else {return};
+    match item {
+        Some(item) => {
+            entity.insert(item);
+        }
+        None => {
+            entity.remove::<T>();
+        }
+    }
+}
+
+/// Fields of a [`WidgetNode`] that make up its [`Transform2D`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TransformNode {
+    anchor: AnchorNode,
+    parent_anchor: Option<AnchorNode>,
+    center: Option<AnchorNode>,
+    offset: Size2,
+    z: f32,
+    rotation: f32,
+    scale: Option<Vec2>,
+}
+
+impl TransformNode {
+    fn new(node: &WidgetNode) -> Self {
+        Self {
+            anchor: node.anchor,
+            parent_anchor: node.parent_anchor,
+            center: node.center,
+            offset: node.offset,
+            z: node.z,
+            rotation: node.rotation,
+            scale: node.scale,
+        }
+    }
+}
+
+/// Fields of a [`WidgetNode`] changed in the asset, other than the transform.
+#[derive(Debug, Clone, Copy, Default)]
+struct NodeChanges {
+    name: bool,
+    opacity: bool,
+    clipping: bool,
+    dimension: bool,
+    font_size: bool,
+    color: bool,
+    event: bool,
+    hitbox: bool,
+    layout: bool,
+    margin: bool,
+    padding: bool,
+    style: bool,
+    sprite: bool,
+    size: bool,
+    flip: bool,
+    text: bool,
+    font: bool,
+    wrap: bool,
+}
+
+impl NodeChanges {
+    /// Compare two nodes of the same widget kind.
+    fn new(old: &WidgetNode, new: &WidgetNode) -> Self {
+        let mut changes = Self {
+            name: old.name != new.name,
+            opacity: old.opacity != new.opacity,
+            clipping: old.clipping != new.clipping,
+            // Layouts change `Copied` to `Dynamic`.
+            dimension: old.dimension != new.dimension || old.layout.is_some() != new.layout.is_some(),
+            font_size: old.font_size != new.font_size,
+            color: old.color != new.color,
+            event: old.event != new.event,
+            // Events add a hitbox if none is specified.
+            hitbox: old.hitbox != new.hitbox || old.event.is_empty() != new.event.is_empty(),
+            layout: old.layout != new.layout,
+            margin: old.margin != new.margin,
+            padding: old.padding != new.padding,
+            style: old.style != new.style,
+            ..Default::default()
+        };
+        match (&old.widget, &new.widget) {
+            (
+                WidgetKind::Sprite { sprite: s1, size: z1, flip: f1 },
+                WidgetKind::Sprite { sprite: s2, size: z2, flip: f2 },
+            ) => {
+                changes.sprite = s1 != s2;
+                changes.size = z1 != z2;
+                changes.flip = f1 != f2;
+            }
+            (WidgetKind::Rectangle { size: z1 }, WidgetKind::Rectangle { size: z2 }) => {
+                changes.size = z1 != z2;
+            }
+            (
+                WidgetKind::Text { text: t1, font: f1, wrap: w1 },
+                WidgetKind::Text { text: t2, font: f2, wrap: w2 },
+            ) => {
+                changes.text = t1 != t2;
+                changes.font = f1 != f2;
+                changes.wrap = w1 != w2;
+            }
+            _ => (),
+        }
+        changes
+    }
+}
+
+/// Copy fields of [`Transform2D`] changed in the asset,
+/// runtime values like scrolled or interpolated offsets are kept otherwise.
+fn patch_transform(world: &mut World, from: Entity, to: Entity, old: TransformNode, new: TransformNode) {
+    let Some(item) = world.get::<Transform2D>(from).copied() else {return};
+    let Some(mut transform) = world.get_mut::<Transform2D>(to) else {return};
+    if old.anchor != new.anchor {
+        transform.anchor = item.anchor;
+    }
+    if old.parent_anchor != new.parent_anchor {
+        transform.parent_anchor = item.parent_anchor;
+    }
+    if old.center != new.center {
+        transform.center = item.center;
+    }
+    if old.offset != new.offset {
+        transform.offset = item.offset;
+    }
+    if old.z != new.z {
+        transform.z = item.z;
+    }
+    if old.rotation != new.rotation {
+        transform.rotation = item.rotation;
+    }
+    if old.scale != new.scale {
+        transform.scale = item.scale;
+    }
+}
+
+/// Copy fields of components changed in the asset,
+/// runtime values like computed opacity, clipping or container ranges are kept otherwise.
+fn patch_fields(world: &mut World, from: Entity, to: Entity, changes: NodeChanges) {
+    if let Some(item) = world.get::<Opacity>(from).copied() {
+        if let Some(mut opacity) = world.get_mut::<Opacity>(to) {
+            if changes.opacity {
+                opacity.opacity = item.opacity;
+            }
+        }
+    }
+    if let Some(item) = world.get::<Clipping>(from).copied() {
+        if let Some(mut clipping) = world.get_mut::<Clipping>(to) {
+            if changes.clipping {
+                clipping.clip = item.clip;
+            }
+        }
+    }
+    if let Some(item) = world.get::<Dimension>(from).copied() {
+        if let Some(mut dimension) = world.get_mut::<Dimension>(to) {
+            if changes.dimension {
+                dimension.dimension = item.dimension;
+            }
+            if changes.font_size {
+                dimension.font_size = item.font_size;
+            }
+        }
+    }
+    if let Some(item) = world.get::<Coloring>(from).copied() {
+        if let Some(mut coloring) = world.get_mut::<Coloring>(to) {
+            if changes.color {
+                coloring.color = item.color;
+            }
+        }
+    }
+    if let Some(item) = world.get::<Sprite>(from).cloned() {
+        if let Some(mut sprite) = world.get_mut::<Sprite>(to) {
+            if changes.color {
+                sprite.color = item.color;
+            }
+            if changes.size {
+                sprite.custom_size = item.custom_size;
+            }
+            if changes.flip {
+                sprite.flip_x = item.flip_x;
+                sprite.flip_y = item.flip_y;
+            }
+        }
+    }
+    if let Some(item) = world.get::<Text>(from).cloned() {
+        if let Some(mut text) = world.get_mut::<Text>(to) {
+            for (section, new) in text.sections.iter_mut().zip(item.sections) {
+                if changes.text {
+                    section.value = new.value;
+                }
+                if changes.font {
+                    section.style.font = new.style.font;
+                }
+                if changes.color {
+                    section.style.color = new.style.color;
+                }
+            }
+            if changes.wrap {
+                text.linebreak_behavior = item.linebreak_behavior;
+            }
+        }
+    }
+    let container = world.get::<Container>(from).cloned();
+    match (container, world.get_mut::<Container>(to)) {
+        (Some(item), Some(mut container)) => {
+            if changes.layout {
+                container.layout = item.layout;
+            }
+            if changes.margin {
+                container.margin = item.margin;
+            }
+            if changes.padding {
+                container.padding = item.padding;
+            }
+        }
+        // Added or removed by `layout`.
+        _ => patch::<Container>(world, from, to, changes.layout),
+    }
+}
+
+/// Copy components and fields generated by a widget builder and changed in the asset
+/// from `from` to `to`, then despawn `from` and children generated by the builder.
+///
+/// Components not generated by the builder, like `InputBox` or `Interpolate`, are kept.
+fn patch_widget(world: &mut World, from: Entity, to: Entity, transform: (TransformNode, TransformNode), changes: NodeChanges) {
+    patch::<Name>(world, from, to, changes.name);
+    patch_transform(world, from, to, transform.0, transform.1);
+    patch_fields(world, from, to, changes);
+    patch::<EventFlags>(world, from, to, changes.event);
+    patch::<Hitbox>(world, from, to, changes.hitbox);
+    patch::<Styled>(world, from, to, changes.style);
+    patch::<Handle<Image>>(world, from, to, changes.sprite);
+    patch::<Handle<Font>>(world, from, to, changes.font);
+    patch::<bevy::sprite::Anchor>(world, from, to, transform.0.anchor != transform.1.anchor);
+    despawn_with_children_recursive(world, from);
+}
+
+/// Place managed children in order, followed by children not spawned from the asset.
+fn reorder_children(world: &mut World, container: Entity, order: Vec<Entity>) {
+    let Some(mut entity) = world.get_entity_mut(container) else {return};
+    let mut children = order.clone();
+    if let Some(current) = entity.get::<Children>() {
+        children.extend(current.iter().filter(|x| !order.contains(x)));
+    }
+    entity.replace_children(&children);
+}
+
+/// Update a spawned tree from `old` to `new`.
+///
+/// Entities of nodes with unchanged names and paths are kept, and updated in place if needed.
+/// Nodes with a different widget kind, signals or callbacks are rebuilt with their children.
+pub(crate) fn reconcile(
+    old: &WidgetNode,
+    new: &WidgetNode,
+    spawned: SpawnedNode,
+    commands: &mut RCommands,
+    registry: &UiRegistry,
+) -> SpawnedNode {
+    if discriminant(&old.widget) != discriminant(&new.widget)
+            || old.signals != new.signals
+            || old.callbacks != new.callbacks {
+        commands.despawn(spawned.entity);
+        return spawn_tracked(new, commands, registry);
+    }
+    let SpawnedNode { entity, container, children: spawned_children } = spawned;
+    if !old.eq_ignore_children(new) {
+        let (temp, _) = new.spawn_widget(commands);
+        let transform = (TransformNode::new(old), TransformNode::new(new));
+        let changes = NodeChanges::new(old, new);
+        commands.add_command(move |world: &mut World| patch_widget(world, temp, entity, transform, changes));
+    }
+    let mut previous: HashMap<_, _> = sibling_keys(&old.children)
+        .zip(old.children.iter().zip(spawned_children))
+        .collect();
+    let children: Vec<_> = sibling_keys(&new.children)
+        .zip(new.children.iter())
+        .map(|(key, child)| match previous.remove(&key) {
+            Some((old, spawned)) => reconcile(old, child, spawned, commands, registry),
+            None => spawn_tracked(child, commands, registry),
+        })
+        .collect();
+    for (_, (_, spawned)) in previous {
+        commands.despawn(spawned.entity);
+    }
+    let order: Vec<_> = children.iter().map(|x| x.entity).collect();
+    commands.add_command(move |world: &mut World| reorder_children(world, container, order));
+    SpawnedNode { entity, container, children }
+}
@@ -9,7 +9,7 @@ use bevy::{
     text::{Text2dBounds, TextLayoutInfo}
 };
 
-use crate::{Transform2D, RotatedRect, BuildTransform, Hitbox, layout::LayoutControl, Size2, Opacity, Anchor, Clipping, DimensionData, Dimension, Coloring};
+use crate::{Transform2D, RootEntity, RotatedRect, BuildTransform, Hitbox, layout::LayoutControl, Size2, Opacity, Anchor, Clipping, DimensionData, Dimension, Coloring};
 
 
 /// The minimal bundle required for bevy_rectray's pipeline to function.
@@ -24,6 +24,7 @@ pub struct RectrayBundle {
     pub rect: RotatedRect,
     pub clipping: Clipping,
     pub opacity: Opacity,
+    pub root: RootEntity,
     pub vis: VisibilityBundle,
 }
 
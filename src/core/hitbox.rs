@@ -5,7 +5,8 @@ use bevy::prelude::*;
 use crate::RotatedRect;
 
 /// Shape of a hitbox.
-#[derive(Debug, Clone, Copy, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum HitboxShape{
     Rect,
@@ -13,7 +14,8 @@ pub enum HitboxShape{
 }
 
 /// Provides cursor detection on [`RotatedRect`]
-#[derive(Debug, Clone, Copy, Component, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hitbox {
     pub shape: HitboxShape,
     pub scale: Vec2,
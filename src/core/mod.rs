@@ -4,7 +4,9 @@ pub(crate) mod dimension;
 pub(crate) mod components;
 pub(crate) mod hitbox;
 pub(crate) mod pipeline;
+pub(crate) mod root;
 pub(crate) mod scaling;
+pub(crate) mod snapping;
 pub(crate) mod systems;
 pub(crate) mod transform;
 
@@ -12,8 +14,11 @@ pub use rect::*;
 pub use components::*;
 pub use hitbox::*;
 pub use scaling::*;
+pub use snapping::{PixelSnapping, PixelSnap, PixelSnapQuery};
 
 pub use transform::{Transform2D, BuildTransform, BuildMeshTransform};
 pub use dimension::{Dimension, DimensionData, DimensionType, DimensionMut};
+pub use pipeline::{RootQuery, compute_aoui_transforms, compute_aoui_opacity};
+pub use root::{RectrayRoot, RootEntity, RootRects, hide_projected_roots};
 
 pub mod bundles;
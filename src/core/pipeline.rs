@@ -1,9 +1,11 @@
 use std::mem;
 
-use bevy::{ecs::query::{QueryData, QueryFilter}, math::Affine2, prelude::*, window::PrimaryWindow};
+use bevy::{ecs::query::{QueryData, QueryFilter}, prelude::*, window::PrimaryWindow};
 
 use crate::{*, layout::*};
 
+use super::root::{centered_rect, RootRects};
+
 type REntity<'t> = (
     Entity,
     DimensionMut,
@@ -11,6 +13,7 @@ type REntity<'t> = (
     &'t mut RotatedRect,
     &'t mut Opacity,
     &'t mut Clipping,
+    Option<&'t mut RootEntity>,
     &'t LayoutControl,
 );
 
@@ -26,7 +29,7 @@ fn propagate(
     layout_query: &mut Query<&mut Container>,
     parent_query: &Query<&Parent>,
     child_query: &Query<&Children>,
-    not_root: &Query<Entity, Without<Detach>>,
+    not_root: &Query<Entity, (Without<Detach>, Without<RectrayRoot>)>,
     queue: &mut Vec<(Entity, ParentInfo)>) {
 
     if !mut_query.contains(entity) { return; }
@@ -36,7 +39,7 @@ fn propagate(
     }
 
     // SAFETY: safe since double mut access is gated by the hierarchy check
-    let Ok((entity, mut dim, transform, mut orig, mut opacity, mut clipping, ..))
+    let Ok((entity, mut dim, transform, mut orig, mut opacity, mut clipping, root, ..))
         = (unsafe {mut_query.get_unchecked(entity)}) else {return};
 
     let (dimension, em) = dim.update(parent.dimension, parent.em, rem);
@@ -46,6 +49,12 @@ fn propagate(
 
     opacity.occluded = false;
 
+    if let Some(mut root) = root {
+        if root.0 != parent.root {
+            root.0 = parent.root;
+        }
+    }
+
     if let Ok(mut layout) = layout_query.get_mut(entity) {
         let children = not_root.iter_many(child_query.get(entity).map(|x| x.iter()).into_iter().flatten());
         let mut other_entities = Vec::new();
@@ -108,6 +117,7 @@ fn propagate(
 
         let info = ParentInfo {
             entity: Some(entity),
+            root: parent.root,
             rect,
             anchor: None,
             dimension: size,
@@ -145,6 +155,7 @@ fn propagate(
     if let Ok(children) = child_query.get(entity) {
         let info = ParentInfo {
             entity: Some(entity),
+            root: parent.root,
             rect,
             anchor: None,
             dimension,
@@ -163,7 +174,9 @@ fn propagate(
 
 /// Query for finding the root rectangle of a `compute_aoui_transforms` pass.
 ///
-/// Usually `PrimaryWindow`.
+/// Usually `PrimaryWindow`, `RectrayCamera` or `Camera` uses the viewport of a camera instead.
+///
+/// Entities with [`RectrayRoot`] use their own root rectangles.
 pub trait RootQuery<'t> {
     type Query: QueryData;
     type ReadOnly: QueryFilter;
@@ -180,16 +193,21 @@ impl<'t> RootQuery<'t> for PrimaryWindow {
             Ok(w) => w,
             Err(_) => return Default::default(),
         };
-        let dim = Vec2::new(window.width(), window.height());
-        (RotatedRect {
-            affine: Affine2::from_scale(dim),
-            rotation: 0.0,
-            scale: Vec2::ONE,
-            z: 0.0
-        }, dim)
+        centered_rect(Vec2::new(window.width(), window.height()))
     }
 }
 
+/// Scale a root rectangle, keeping its size in world space.
+fn scale_root((rect, dimension): (RotatedRect, Vec2), scale: f32) -> (RotatedRect, Vec2) {
+    if scale == 1.0 {
+        return (rect, dimension);
+    }
+    (RotatedRect {
+        scale: rect.scale * scale,
+        ..rect
+    }, dimension / scale)
+}
+
 /// The main computation step.
 ///
 /// For custom usage,
@@ -199,24 +217,31 @@ impl<'t> RootQuery<'t> for PrimaryWindow {
 /// TRoot: Readonly query for child of root rectangle.
 ///
 /// TAll: Readonly query for all children, including TRoot.
+///
+/// Entities with [`RectrayRoot`] are laid out against their own root rectangles.
 #[allow(clippy::too_many_arguments)]
 pub fn compute_aoui_transforms<'t, R: RootQuery<'t>>(
     root: Query<R::Query, R::ReadOnly>,
-    root_entities: Query<Entity, Or<(Without<Parent>, With<Detach>)>>,
+    root_entities: Query<Entity, (Or<(Without<Parent>, With<Detach>)>, Without<RectrayRoot>)>,
+    rooted_entities: Query<(Entity, &RectrayRoot)>,
+    root_rects: RootRects,
     mut entity_query: Query<REntity>,
     mut layout_query: Query<&mut Container>,
     parent_query: Query<&Parent>,
     child_query: Query<&Children>,
-    not_root: Query<Entity, Without<Detach>>,
+    not_root: Query<Entity, (Without<Detach>, Without<RectrayRoot>)>,
     res_rem: Option<Res<RectrayRem>>,
+    scaling: Option<Res<UiScaling>>,
 ) {
     let rem = res_rem.map(|x| x.get()).unwrap_or(16.0);
+    let root_scale = UiScaling::root_scale(scaling.as_deref());
 
-    let (window_rect, dimension) = R::as_rect(&root);
+    let (window_rect, dimension) = scale_root(R::as_rect(&root), root_scale);
 
     let mut queue = Vec::new();
     let window_info = ParentInfo {
         entity: None,
+        root: None,
         rect: window_rect,
         anchor: None,
         dimension,
@@ -228,6 +253,17 @@ pub fn compute_aoui_transforms<'t, R: RootQuery<'t>>(
         queue.push((entity, window_info))
     }
 
+    for (entity, target) in rooted_entities.iter() {
+        let Some(root) = root_rects.get(target) else {continue};
+        let (rect, dimension) = match target {
+            RectrayRoot::Camera(_) | RectrayRoot::Window(_) => scale_root(root, root_scale),
+            _ => root,
+        };
+        if entity_query.contains(entity) {
+            queue.push((entity, ParentInfo { root: Some(entity), rect, dimension, ..window_info }))
+        }
+    }
+
     while !queue.is_empty() {
         for (entity, parent) in std::mem::take(&mut queue) {
             propagate(parent,
@@ -129,6 +129,7 @@ pub enum PointOrRect {
 #[derive(Debug, Copy, Clone)]
 pub struct ParentInfo {
     pub entity: Option<Entity>,
+    pub root: Option<Entity>,
     pub rect: RotatedRect,
     pub anchor: Option<Vec2>,
     pub dimension: Vec2,
@@ -0,0 +1,268 @@
+use bevy::asset::{Assets, Handle};
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::{Entity, EntityHashSet};
+use bevy::ecs::query::With;
+use bevy::ecs::system::{Local, Query, Res, SystemParam};
+use bevy::math::{Affine2, EulerRot, Mat2, Vec2};
+use bevy::render::camera::{Camera, NormalizedRenderTarget};
+use bevy::render::texture::Image;
+use bevy::render::view::Visibility;
+use bevy::transform::components::GlobalTransform;
+use bevy::transform::helper::TransformHelper;
+use bevy::window::{PrimaryWindow, Window};
+
+use crate::events::{CameraQuery, RectrayCamera};
+use crate::{RootQuery, RotatedRect};
+
+/// The entity with the [`RectrayRoot`] an entity is laid out against, written during layout.
+///
+/// `None` if laid out against the `PrimaryWindow`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Component)]
+pub struct RootEntity(pub Option<Entity>);
+
+/// Lays out an entity and its descendants against a specific root rectangle,
+/// instead of the `PrimaryWindow`.
+///
+/// Like [`Detach`](crate::Detach), this breaks hierarchy if the entity has a parent.
+///
+/// Cursor events of descendants are mapped through the camera of the root,
+/// see [`RootCursor`](crate::events::RootCursor).
+#[derive(Debug, Clone, PartialEq, Component)]
+pub enum RectrayRoot {
+    /// The viewport of a camera in world space, including its position, rotation and scale.
+    ///
+    /// Use this for split screen or a [`ScopedCameraBundle`](crate::widgets::clipping::ScopedCameraBundle).
+    Camera(Entity),
+    /// A window, as the viewport of the active camera with the highest `order` rendering to it,
+    /// or centered at the origin if no such camera exists.
+    Window(Entity),
+    /// The size of an image, centered at the origin.
+    ///
+    /// Entities under an image root do not receive cursor events.
+    Image(Handle<Image>),
+    /// A rectangle of `size` centered at an entity's `GlobalTransform` in 2D world space,
+    /// including its rotation, scale and z.
+    ///
+    /// Use this for health bars and nameplates that move with 2D game entities.
+    Follow {
+        entity: Entity,
+        size: Vec2,
+    },
+    /// A rectangle of `size` in the world space of the camera of [`CameraQuery`],
+    /// centered at an entity's translation projected through a 3D `camera`.
+    ///
+    /// If `clamp` is set, the rectangle is kept inside the viewport,
+    /// otherwise the subtree is hidden while the entity is behind the camera.
+    Projected {
+        entity: Entity,
+        camera: Entity,
+        size: Vec2,
+        clamp: bool,
+    },
+}
+
+impl RectrayRoot {
+    /// Follow an entity in 2D world space, with a root size of zero.
+    pub fn follow(entity: Entity) -> Self {
+        RectrayRoot::Follow { entity, size: Vec2::ZERO }
+    }
+
+    /// Project an entity through a 3D camera, with a root size of zero.
+    pub fn projected(entity: Entity, camera: Entity) -> Self {
+        RectrayRoot::Projected { entity, camera, size: Vec2::ZERO, clamp: false }
+    }
+
+    /// Set the size of a `Follow` or `Projected` root.
+    pub fn with_size(mut self, value: Vec2) -> Self {
+        match &mut self {
+            RectrayRoot::Follow { size, .. } | RectrayRoot::Projected { size, .. } => *size = value,
+            _ => (),
+        }
+        self
+    }
+
+    /// Keep a `Projected` root inside the viewport.
+    pub fn clamped(mut self) -> Self {
+        if let RectrayRoot::Projected { clamp, .. } = &mut self {
+            *clamp = true;
+        }
+        self
+    }
+}
+
+/// Root rectangle of a size centered at the origin.
+pub(crate) fn centered_rect(dimension: Vec2) -> (RotatedRect, Vec2) {
+    (RotatedRect {
+        affine: Affine2::from_scale(dimension),
+        rotation: 0.0,
+        scale: Vec2::ONE,
+        z: 0.0,
+    }, dimension)
+}
+
+/// Root rectangle of the viewport of a camera in world space.
+///
+/// The dimension is the logical size of the viewport, scaled to world space,
+/// so pixel sizes stay constant on screen.
+pub(crate) fn camera_rect(camera: &Camera, transform: &GlobalTransform) -> Option<(RotatedRect, Vec2)> {
+    let size = camera.logical_viewport_size()?;
+    let bottom_left = camera.viewport_to_world_2d(transform, Vec2::new(0.0, size.y))?;
+    let bottom_right = camera.viewport_to_world_2d(transform, size)?;
+    let top_left = camera.viewport_to_world_2d(transform, Vec2::ZERO)?;
+    let x = bottom_right - bottom_left;
+    let y = top_left - bottom_left;
+    Some((RotatedRect {
+        affine: Affine2::from_mat2_translation(Mat2::from_cols(x, y), (bottom_right + top_left) / 2.0),
+        rotation: x.to_angle(),
+        scale: Vec2::new(x.length(), y.length()) / size,
+        z: 0.0,
+    }, size))
+}
+
+/// Check if a camera renders to a window.
+pub(crate) fn renders_to_window(camera: &Camera, window: Entity, primary: Option<Entity>) -> bool {
+    match camera.target.normalize(primary) {
+        Some(NormalizedRenderTarget::Window(target)) => target.entity() == window,
+        _ => false,
+    }
+}
+
+/// Find the active camera with the highest `order` rendering to a window.
+pub(crate) fn window_camera<'t>(
+    cameras: impl IntoIterator<Item = (Entity, &'t Camera, &'t GlobalTransform)>,
+    window: Entity,
+    primary: Option<Entity>,
+) -> Option<(Entity, &'t Camera, &'t GlobalTransform)> {
+    cameras.into_iter()
+        .filter(|(_, camera, _)| camera.is_active && renders_to_window(camera, window, primary))
+        .max_by_key(|(_, camera, _)| camera.order)
+}
+
+impl<'t> RootQuery<'t> for RectrayCamera {
+    type Query = (&'t Camera, &'t GlobalTransform);
+    type ReadOnly = With<RectrayCamera>;
+
+    fn as_rect(query: &Query<Self::Query, Self::ReadOnly>) -> (RotatedRect, Vec2) {
+        query.get_single().ok()
+            .and_then(|(camera, transform)| camera_rect(camera, transform))
+            .unwrap_or_default()
+    }
+}
+
+impl<'t> RootQuery<'t> for Camera {
+    type Query = (&'t Camera, &'t GlobalTransform);
+    type ReadOnly = ();
+
+    /// Viewport of the camera with the highest `order`.
+    fn as_rect(query: &Query<Self::Query, Self::ReadOnly>) -> (RotatedRect, Vec2) {
+        query.iter()
+            .filter(|(camera, _)| camera.is_active)
+            .max_by_key(|(camera, _)| camera.order)
+            .and_then(|(camera, transform)| camera_rect(camera, transform))
+            .unwrap_or_default()
+    }
+}
+
+/// Query for root rectangles of [`RectrayRoot`]s.
+#[derive(SystemParam)]
+pub struct RootRects<'w, 's> {
+    camera: CameraQuery<'w, 's>,
+    transforms: TransformHelper<'w, 's>,
+    cameras: Query<'w, 's, (Entity, &'static Camera, &'static GlobalTransform)>,
+    windows: Query<'w, 's, &'static Window>,
+    primary: Query<'w, 's, Entity, With<PrimaryWindow>>,
+    images: Option<Res<'w, Assets<Image>>>,
+}
+
+impl RootRects<'_, '_> {
+    /// Find the root rectangle and its dimension, `None` if the target does not exist.
+    pub fn get(&self, root: &RectrayRoot) -> Option<(RotatedRect, Vec2)> {
+        match root {
+            RectrayRoot::Camera(entity) => {
+                let (_, camera, transform) = self.cameras.get(*entity).ok()?;
+                camera_rect(camera, transform)
+            },
+            RectrayRoot::Window(entity) => {
+                let window = self.windows.get(*entity).ok()?;
+                match window_camera(&self.cameras, *entity, self.primary.get_single().ok()) {
+                    Some((_, camera, transform)) => camera_rect(camera, transform),
+                    None => Some(centered_rect(Vec2::new(window.width(), window.height()))),
+                }
+            },
+            RectrayRoot::Image(handle) => {
+                let image = self.images.as_ref()?.get(handle)?;
+                Some(centered_rect(image.size_f32()))
+            },
+            RectrayRoot::Follow { entity, size } => {
+                // Computed here since `GlobalTransform` is not propagated yet on this frame.
+                let transform = self.transforms.compute_global_transform(*entity).ok()?;
+                let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+                let (rotation, ..) = rotation.to_euler(EulerRot::ZYX);
+                let scale = scale.truncate();
+                Some((RotatedRect {
+                    affine: Affine2::from_scale_angle_translation(*size * scale, rotation, translation.truncate()),
+                    rotation,
+                    scale,
+                    z: translation.z,
+                }, *size))
+            },
+            RectrayRoot::Projected { entity, camera, size, clamp } => {
+                self.project(*entity, *camera, *size, *clamp)
+            },
+        }
+    }
+
+    fn project(&self, entity: Entity, camera: Entity, size: Vec2, clamp: bool) -> Option<(RotatedRect, Vec2)> {
+        let position = self.transforms.compute_global_transform(entity).ok()?.translation();
+        let (_, camera_3d, _) = self.cameras.get(camera).ok()?;
+        let transform_3d = self.transforms.compute_global_transform(camera).ok()?;
+        let viewport_3d = camera_3d.logical_viewport_rect()?;
+        let ndc = camera_3d.world_to_ndc(&transform_3d, position)?;
+        let mut xy = ndc.truncate();
+        if !(0.0..=1.0).contains(&ndc.z) {
+            if !clamp {
+                return None;
+            }
+            // Behind the camera, push to the edge in the opposite direction.
+            xy = -xy / xy.abs().max_element().max(f32::EPSILON);
+        }
+        let screen = viewport_3d.min + Vec2::new(xy.x + 1.0, 1.0 - xy.y) / 2.0 * viewport_3d.size();
+        let (camera, transform) = self.camera.get()?;
+        let viewport = camera.logical_viewport_rect()?;
+        let mut center = camera.viewport_to_world_2d(transform, screen - viewport.min)?;
+        if clamp {
+            let (rect, dimension) = camera_rect(camera, transform)?;
+            let half = ((dimension * rect.scale - size) / 2.0).max(Vec2::ZERO);
+            let local = rect.local_space(center).clamp(-half, half);
+            center = rect.center() + Vec2::from_angle(rect.rotation).rotate(local);
+        }
+        Some((RotatedRect {
+            affine: Affine2::from_scale_angle_translation(size, 0.0, center),
+            rotation: 0.0,
+            scale: Vec2::ONE,
+            z: 0.0,
+        }, size))
+    }
+}
+
+/// Hide unclamped `Projected` roots while their entity is behind the camera.
+///
+/// Runs after `Visibility` is synchronized from `Opacity`,
+/// only restores visibility of roots hidden by this system.
+pub fn hide_projected_roots(
+    root_rects: RootRects,
+    mut hidden: Local<EntityHashSet>,
+    mut query: Query<(Entity, &RectrayRoot, &mut Visibility)>,
+) {
+    for (entity, root, mut vis) in query.iter_mut() {
+        let behind = matches!(root, RectrayRoot::Projected { clamp: false, .. }) && root_rects.get(root).is_none();
+        if behind {
+            hidden.insert(entity);
+            if *vis != Visibility::Hidden {
+                *vis = Visibility::Hidden;
+            }
+        } else if hidden.remove(&entity) && *vis == Visibility::Hidden {
+            *vis = Visibility::Inherited;
+        }
+    }
+}
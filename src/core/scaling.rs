@@ -1,4 +1,6 @@
-use bevy::{prelude::{Vec2, Resource}, reflect::Reflect};
+use bevy::{prelude::{Vec2, Resource, ResMut}, reflect::Reflect};
+
+use crate::util::WindowSize;
 
 /// The root font size of the window.
 ///
@@ -21,8 +23,114 @@ impl Default for RectrayRem {
     }
 }
 
+/// How [`UiScaling`] compares the window to the reference resolution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum ScaleMode {
+    /// Scale by the ratio of widths.
+    MatchWidth,
+    /// Scale by the ratio of heights.
+    MatchHeight,
+    /// Scale by the smaller ratio, the reference resolution always fits in the window.
+    #[default]
+    Expand,
+    /// Scale by the larger ratio, the window always fits in the reference resolution.
+    Shrink,
+}
+
+/// Scales the UI with the size of the `PrimaryWindow` relative to a reference resolution,
+/// optional.
+///
+/// By default this sets [`RectrayRem`] to `rem * scale`, which scales `em` and `rem` units,
+/// including [`FontSize::Rems`] and [`SizeUnit::Rem`].
+///
+/// If `scale_root` is set, [`RectrayRem`] is set to `rem` and the root rectangle is scaled instead,
+/// which scales all units, except percentages, which still fill the window.
+/// This applies to the `PrimaryWindow` and to camera and window [`RectrayRoot`](crate::RectrayRoot)s.
+#[derive(Debug, Clone, Copy, PartialEq, Resource, Reflect)]
+pub struct UiScaling {
+    /// The resolution the UI is designed at.
+    pub reference: Vec2,
+    pub mode: ScaleMode,
+    /// [`RectrayRem`] at the reference resolution.
+    pub rem: f32,
+    /// Scale the root rectangle instead of [`RectrayRem`].
+    pub scale_root: bool,
+    scale: f32,
+}
+
+impl UiScaling {
+    /// Create a [`UiScaling`] with a `rem` of `16 px`.
+    pub fn new(reference: Vec2, mode: ScaleMode) -> Self {
+        Self {
+            reference,
+            mode,
+            rem: 16.0,
+            scale_root: false,
+            scale: 1.0,
+        }
+    }
+
+    /// Set [`RectrayRem`] at the reference resolution.
+    pub fn with_rem(mut self, rem: f32) -> Self {
+        self.rem = rem;
+        self
+    }
+
+    /// Scale the root rectangle instead of [`RectrayRem`].
+    pub fn with_root_scale(mut self) -> Self {
+        self.scale_root = true;
+        self
+    }
+
+    /// The computed scale, `1.0` at the reference resolution.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Compute the scale from the size of the window.
+    pub fn compute_scale(&self, window: Vec2) -> f32 {
+        let ratio = window / self.reference;
+        let scale = match self.mode {
+            ScaleMode::MatchWidth => ratio.x,
+            ScaleMode::MatchHeight => ratio.y,
+            ScaleMode::Expand => ratio.min_element(),
+            ScaleMode::Shrink => ratio.max_element(),
+        };
+        if scale.is_finite() && scale > 0.0 { scale } else { 1.0 }
+    }
+
+    /// The scale applied to root rectangles.
+    pub(crate) fn root_scale(scaling: Option<&Self>) -> f32 {
+        match scaling {
+            Some(scaling) if scaling.scale_root => scaling.scale,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Update [`UiScaling`] and [`RectrayRem`] with the size of the window.
+pub fn update_ui_scaling(
+    window: WindowSize,
+    scaling: Option<ResMut<UiScaling>>,
+    mut rem: ResMut<RectrayRem>,
+) {
+    let Some(mut scaling) = scaling else {return};
+    let size = window.get();
+    if size.cmple(Vec2::ZERO).any() {
+        return;
+    }
+    let scale = scaling.compute_scale(size);
+    if scaling.scale != scale {
+        scaling.scale = scale;
+    }
+    let value = if scaling.scale_root {scaling.rem} else {scaling.rem * scale};
+    if rem.get() != value {
+        rem.set(value);
+    }
+}
+
 /// Set the font size of the widget.
-#[derive(Debug, Clone, Copy, Default, Reflect)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
 #[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FontSize {
     #[default]
@@ -0,0 +1,53 @@
+use bevy::ecs::component::Component;
+use bevy::ecs::system::{Res, Resource, SystemParam};
+use bevy::math::Vec2;
+use bevy::reflect::Reflect;
+
+use crate::util::ScalingFactor;
+use crate::RotatedRect;
+
+/// Rounds the translation and size of computed rectangles to the physical pixel grid,
+/// disabled by default.
+///
+/// This happens before `GlobalTransform`s are built and does not affect layout.
+/// The grid is aligned to the world origin, and assumes a camera
+/// of scale `1` at an integer position.
+///
+/// Rotated entities are never snapped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Resource, Reflect)]
+pub struct PixelSnapping(pub bool);
+
+/// Overrides [`PixelSnapping`] for an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Reflect)]
+pub struct PixelSnap(pub bool);
+
+/// Query for snapping rectangles to the physical pixel grid.
+#[derive(SystemParam)]
+pub struct PixelSnapQuery<'w, 's> {
+    global: Option<Res<'w, PixelSnapping>>,
+    scaling_factor: ScalingFactor<'w, 's>,
+}
+
+impl PixelSnapQuery<'_, '_> {
+    /// Check if an entity should be snapped.
+    pub fn enabled(&self, local: Option<&PixelSnap>) -> bool {
+        match local {
+            Some(PixelSnap(value)) => *value,
+            None => self.global.as_ref().is_some_and(|x| x.0),
+        }
+    }
+
+    /// Snap a rectangle with a world space `size`,
+    /// returns the bottom left corner and size, or `None` if not snapped.
+    ///
+    /// Size is rounded separately so it does not change with position.
+    pub fn snap(&self, local: Option<&PixelSnap>, rect: &RotatedRect, size: Vec2) -> Option<(Vec2, Vec2)> {
+        if rect.rotation != 0.0 || !self.enabled(local) {
+            return None;
+        }
+        let factor = self.scaling_factor.get();
+        let min = ((rect.center() - size / 2.0) * factor).round() / factor;
+        let size = (size * factor).round() / factor;
+        Some((min, size))
+    }
+}
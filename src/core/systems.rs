@@ -5,7 +5,7 @@ use bevy::prelude::*;
 use bevy::sprite::Anchor as BevyAnchor;
 use crate::dimension::DimensionMut;
 use crate::util::ScalingFactor;
-use crate::{RotatedRect, BuildTransform, Transform2D, Opacity, IgnoreAlpha, BuildMeshTransform, Anchor, DimensionData, Dimension, Coloring};
+use crate::{PixelSnap, PixelSnapQuery, RotatedRect, BuildTransform, Transform2D, Opacity, IgnoreAlpha, BuildMeshTransform, Anchor, DimensionData, Dimension, Coloring};
 
 
 /// Copy [`Anchor`](BevyAnchor) component's value to the [`Transform2D`] component
@@ -67,12 +67,20 @@ pub fn copy_dimension_atlas(
 
 /// Synchonize size from `Dimension` to `Sprite`
 pub fn sync_dimension_sprite(
-    mut query: Query<(&mut Sprite, &Dimension, &DimensionData)>
+    snapping: PixelSnapQuery,
+    mut query: Query<(&mut Sprite, &Dimension, &DimensionData, Option<&RotatedRect>, Option<&PixelSnap>)>
 ) {
-    //let scaling_factor = scaling_factor.get();
-    query.iter_mut().for_each(|(mut sp, dimension, data)| {
-        if !dimension.is_copied() && sp.custom_size != Some(data.size) {
-            sp.custom_size = Some(data.size)
+    query.iter_mut().for_each(|(mut sp, dimension, data, rect, snap)| {
+        if dimension.is_copied() {
+            return;
+        }
+        let snapped = rect.and_then(|rect| Some((rect, snapping.snap(snap, rect, rect.scale * data.size)?)));
+        let size = match snapped {
+            Some((rect, (_, size))) => size / rect.scale,
+            None => data.size,
+        };
+        if sp.custom_size != Some(size) {
+            sp.custom_size = Some(size)
         }
     })
 }
@@ -148,26 +156,39 @@ pub fn sync_opacity_sprite(mut query: Query<(&Coloring, &Opacity, &mut Sprite),
 }
 
 pub fn build_mesh_2d_global_transform(
-    mut query: Query<(&RotatedRect, &DimensionData, &mut GlobalTransform), With<BuildMeshTransform>>
+    snapping: PixelSnapQuery,
+    mut query: Query<(&RotatedRect, &DimensionData, Option<&PixelSnap>, &mut GlobalTransform), With<BuildMeshTransform>>
 ) {
-    query.iter_mut().for_each(|(rect, dim, mut transform)|
+    query.iter_mut().for_each(|(rect, dim, snap, mut transform)| {
+        let size = rect.scale * dim.size;
+        let (center, size) = match snapping.snap(snap, rect, size) {
+            Some((min, size)) => (min + size / 2.0, size),
+            None => (rect.anchor(Anchor::CENTER), size),
+        };
         *transform = Affine3A::from_scale_rotation_translation(
-            (rect.scale * dim.size).extend(1.0),
+            size.extend(1.0),
             Quat::from_rotation_z(rect.rotation),
-            rect.anchor(Anchor::CENTER).extend(rect.z)
+            center.extend(rect.z)
         ).into()
-    );
+    });
 }
 
 /// Generate [`GlobalTransform`] with  [`BuildTransform`].
 pub fn build_global_transform(
-    mut query: Query<(&BuildTransform, &Transform2D, &RotatedRect, &mut GlobalTransform)>,
+    snapping: PixelSnapQuery,
+    mut query: Query<(&BuildTransform, &Transform2D, &RotatedRect, Option<&DimensionData>, Option<&PixelSnap>, &mut GlobalTransform)>,
 ) {
-    query.iter_mut().for_each(|(build, transform, rect, mut global)| {
+    query.iter_mut().for_each(|(build, transform, rect, dim, snap, mut global)| {
+        let anchor = build.0.or(transform.anchor);
+        let size = rect.scale * dim.map(|x| x.size).unwrap_or(Vec2::ZERO);
+        let translation = match snapping.snap(snap, rect, size) {
+            Some((min, size)) => min + anchor.as_unit() * size,
+            None => rect.anchor(anchor),
+        };
         *global = Affine3A::from_scale_rotation_translation(
             rect.scale.extend(1.0),
             Quat::from_rotation_z(rect.rotation),
-            rect.anchor(build.0.or(transform.anchor)).extend(rect.z)
+            translation.extend(rect.z)
         ).into()
     });
 }
@@ -0,0 +1,261 @@
+//! Debug overlay for inspecting layouts, requires feature `debug`.
+//!
+//! Add [`RectrayDebugPlugin`] to draw outlines of all rectray entities with bevy's gizmos.
+//! The overlay is configured and toggled at runtime through the [`DebugOverlay`] resource.
+//!
+//! Outline colors distinguish [`DimensionType`]s, by default
+//!
+//! * `Copied`: green
+//! * `Dynamic`: yellow
+//! * `Owned`: cyan
+
+use std::f32::consts::TAU;
+
+use bevy::app::{Plugin, PostUpdate, Update};
+use bevy::core::Name;
+use bevy::ecs::entity::Entity;
+use bevy::ecs::schedule::IntoSystemConfigs;
+use bevy::ecs::system::{Query, Res, ResMut, Resource};
+use bevy::gizmos::gizmos::Gizmos;
+use bevy::hierarchy::{Children, Parent};
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::math::{Affine2, Vec2};
+use bevy::render::color::Color;
+
+use crate::layout::Container;
+use crate::schedule::FinalizeSet;
+use crate::{Clipping, Dimension, DimensionData, DimensionType, Hitbox, HitboxShape, RectrayRem, RotatedRect, Transform2D};
+
+/// Filters entities drawn by the [`DebugOverlay`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DebugFilter {
+    /// Draw all entities.
+    #[default]
+    All,
+    /// Draw entities with this [`Name`].
+    Name(String),
+    /// Draw this entity and its descendants.
+    Subtree(Entity),
+    /// Draw entities with this [`Name`] and their descendants.
+    NamedSubtree(String),
+}
+
+impl DebugFilter {
+    fn matches(&self, entity: Entity, query: &Query<(Option<&Name>, Option<&Parent>)>) -> bool {
+        let name_is = |entity: Entity, name: &str| matches!(query.get(entity), Ok((Some(n), _)) if n.as_str() == name);
+        let any_ancestor = |f: &dyn Fn(Entity) -> bool| {
+            let mut current = Some(entity);
+            while let Some(entity) = current {
+                if f(entity) {
+                    return true;
+                }
+                current = query.get(entity).ok().and_then(|(_, parent)| parent).map(|x| x.get());
+            }
+            false
+        };
+        match self {
+            DebugFilter::All => true,
+            DebugFilter::Name(name) => name_is(entity, name),
+            DebugFilter::Subtree(root) => any_ancestor(&|e| e == *root),
+            DebugFilter::NamedSubtree(name) => any_ancestor(&|e| name_is(e, name)),
+        }
+    }
+}
+
+/// Colors used by the [`DebugOverlay`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugColors {
+    /// Outline of [`DimensionType::Copied`].
+    pub copied: Color,
+    /// Outline of [`DimensionType::Dynamic`].
+    pub dynamic: Color,
+    /// Outline of [`DimensionType::Owned`].
+    pub owned: Color,
+    pub anchor: Color,
+    pub parent_anchor: Color,
+    pub hitbox: Color,
+    pub clipping: Color,
+    pub padding: Color,
+    pub margin: Color,
+}
+
+impl Default for DebugColors {
+    fn default() -> Self {
+        Self {
+            copied: Color::GREEN,
+            dynamic: Color::YELLOW,
+            owned: Color::CYAN,
+            anchor: Color::RED,
+            parent_anchor: Color::FUCHSIA,
+            hitbox: Color::ORANGE,
+            clipping: Color::WHITE,
+            padding: Color::PURPLE,
+            margin: Color::rgba(0.5, 0.5, 1.0, 0.5),
+        }
+    }
+}
+
+/// Configuration of the debug overlay.
+#[derive(Debug, Clone, Resource)]
+pub struct DebugOverlay {
+    /// Draw the overlay.
+    pub enabled: bool,
+    /// Toggles `enabled` when pressed, by default `F12`.
+    pub toggle: Option<KeyCode>,
+    pub filter: DebugFilter,
+    /// Draw the outline of [`RotatedRect`].
+    pub rect: bool,
+    /// Draw `anchor` and `parent_anchor`.
+    pub anchors: bool,
+    /// Draw the shape of [`Hitbox`].
+    pub hitbox: bool,
+    /// Draw the region of [`Clipping`].
+    pub clipping: bool,
+    /// Draw padding and margin of [`Container`].
+    pub container: bool,
+    pub colors: DebugColors,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            toggle: Some(KeyCode::F12),
+            filter: DebugFilter::All,
+            rect: true,
+            anchors: true,
+            hitbox: true,
+            clipping: true,
+            container: true,
+            colors: DebugColors::default(),
+        }
+    }
+}
+
+fn draw_rect(gizmos: &mut Gizmos, affine: Affine2, color: Color) {
+    gizmos.linestrip_2d([
+        Vec2::new(-0.5, -0.5),
+        Vec2::new(0.5, -0.5),
+        Vec2::new(0.5, 0.5),
+        Vec2::new(-0.5, 0.5),
+        Vec2::new(-0.5, -0.5),
+    ].map(|x| affine.transform_point2(x)), color);
+}
+
+fn draw_ellipse(gizmos: &mut Gizmos, affine: Affine2, color: Color) {
+    gizmos.linestrip_2d((0..=32).map(|i|
+        affine.transform_point2(Vec2::from_angle(i as f32 / 32.0 * TAU) * 0.5)
+    ), color);
+}
+
+fn draw_point(gizmos: &mut Gizmos, point: Vec2, color: Color) {
+    gizmos.circle_2d(point, 3.0, color);
+}
+
+pub(crate) fn toggle_debug_overlay(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut overlay: ResMut<DebugOverlay>,
+) {
+    let (Some(keys), Some(key)) = (keys, overlay.toggle) else {return};
+    if keys.just_pressed(key) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+pub(crate) fn draw_debug_overlay(
+    mut gizmos: Gizmos,
+    overlay: Res<DebugOverlay>,
+    rem: Option<Res<RectrayRem>>,
+    hierarchy: Query<(Option<&Name>, Option<&Parent>)>,
+    parents: Query<(&RotatedRect, &DimensionData, Option<&Container>)>,
+    query: Query<(
+        Entity,
+        &RotatedRect,
+        &Transform2D,
+        &Dimension,
+        &DimensionData,
+        Option<&Parent>,
+        Option<&Hitbox>,
+        Option<&Clipping>,
+        Option<&Container>,
+        Option<&Children>,
+    )>,
+) {
+    if !overlay.enabled {
+        return;
+    }
+    let colors = &overlay.colors;
+    let rem = rem.map(|x| x.get()).unwrap_or(16.0);
+    for (entity, rect, transform, dimension, data, parent, hitbox, clipping, container, children) in query.iter() {
+        if !overlay.filter.matches(entity, &hierarchy) {
+            continue;
+        }
+        if overlay.rect {
+            let color = match dimension.dimension {
+                DimensionType::Copied => colors.copied,
+                DimensionType::Dynamic => colors.dynamic,
+                DimensionType::Owned(_) => colors.owned,
+            };
+            draw_rect(&mut gizmos, rect.affine, color);
+        }
+        if overlay.anchors {
+            let anchor = rect.anchor(transform.anchor);
+            draw_point(&mut gizmos, anchor, colors.anchor);
+            // Parent anchors of children in containers are decided by layouts.
+            let parent = parent.and_then(|x| parents.get(x.get()).ok());
+            if let Some((parent_rect, _, None)) = parent {
+                let parent_anchor = parent_rect.anchor(transform.get_parent_anchor());
+                draw_point(&mut gizmos, parent_anchor, colors.parent_anchor);
+                gizmos.line_2d(parent_anchor, anchor, colors.parent_anchor);
+            }
+        }
+        if overlay.hitbox {
+            if let Some(hitbox) = hitbox {
+                let affine = rect.affine * Affine2::from_scale(hitbox.scale);
+                match hitbox.shape {
+                    HitboxShape::Rect => draw_rect(&mut gizmos, affine, colors.hitbox),
+                    HitboxShape::Ellipse => draw_ellipse(&mut gizmos, affine, colors.hitbox),
+                }
+            }
+        }
+        if overlay.clipping && clipping.is_some_and(|x| x.clip) {
+            draw_rect(&mut gizmos, rect.affine * Affine2::from_scale(Vec2::splat(0.99)), colors.clipping);
+        }
+        let Some(container) = container.filter(|_| overlay.container) else {continue};
+        // Padding and margin are relative to the size of the parent.
+        let parent_size = parent
+            .and_then(|x| parents.get(x.get()).ok())
+            .map(|(_, data, _)| data.size)
+            .unwrap_or(data.size);
+        let padding = container.padding.as_pixels(parent_size, data.em, rem);
+        if padding != Vec2::ZERO && data.size.cmpgt(Vec2::ZERO).all() {
+            let inner = (Vec2::ONE - padding * 2.0 / data.size).max(Vec2::ZERO);
+            draw_rect(&mut gizmos, rect.affine * Affine2::from_scale(inner), colors.padding);
+        }
+        let margin = container.margin.as_pixels(parent_size, data.em, rem);
+        if margin == Vec2::ZERO {
+            continue;
+        }
+        for child in children.into_iter().flatten() {
+            let Ok((child_rect, child_data, _)) = parents.get(*child) else {continue};
+            if child_data.size.cmple(Vec2::ZERO).any() {
+                continue;
+            }
+            let outer = Vec2::ONE + margin / child_data.size;
+            draw_rect(&mut gizmos, child_rect.affine * Affine2::from_scale(outer), colors.margin);
+        }
+    }
+}
+
+/// Plugin for the layout debug overlay, configured by [`DebugOverlay`].
+#[derive(Debug, Default)]
+pub struct RectrayDebugPlugin;
+
+impl Plugin for RectrayDebugPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<DebugOverlay>()
+            .add_systems(Update, toggle_debug_overlay)
+            .add_systems(PostUpdate, draw_debug_overlay.after(FinalizeSet));
+    }
+}
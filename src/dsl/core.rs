@@ -10,6 +10,7 @@ use bevy::render::render_resource::{Extent3d, TextureDimension};
 use crate::{DimensionType, Transform2D, Dimension, Coloring};
 use crate::{frame_extension, Clipping, bundles::{RectrayBundle, BuildTransformBundle}, Hitbox, build_frame, layout::Container};
 
+use crate::style::Styled;
 use crate::util::{Widget, RCommands, convert::IntoAsset};
 use super::Aspect;
 
@@ -93,6 +94,9 @@ impl Widget for FrameBuilder {
         if let Some(layer) = self.layer {
             base.insert(layer);
         }
+        if let Some(style) = self.style {
+            base.insert(Styled(style));
+        }
         if let Some(layout) = self.layout {
             base.insert(Container {
                 layout,
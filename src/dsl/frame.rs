@@ -68,6 +68,8 @@ macro_rules! frame_extension {
             pub padding: $crate::dsl::OneOrTwo<$crate::Size2>,
             /// Displayed range of children, default is all, has no effect if widget has no layout.
             pub children_range: $crate::layout::LayoutRange,
+            /// Name of a style in the `Theme`.
+            pub style: Option<String>,
             $($(#[$($attr)*])* $vis $field: $ty),*
         }
     };
@@ -100,6 +102,7 @@ macro_rules! build_frame {
                 margin: $this.margin,
                 padding: $this.padding,
                 children_range: $this.children_range,
+                style: $this.style,
             }, $commands);
             $commands.entity(entity.0)
         }
@@ -19,6 +19,15 @@
 /// the default value here is needed
 /// and will overwrite the corresponded field.
 ///
+/// * Spring
+///
+/// ```
+/// transition!(Offset spring(300, 20) default [0, 0])
+/// ```
+///
+/// Moves to targets with a [`Spring`](crate::anim::Spring) of stiffness,
+/// damping and optionally mass, keeping velocity when retargeted.
+///
 /// * Init, Repeat and Looping
 ///
 /// Init, repeat and looping will automatically run the animation.
@@ -40,6 +49,30 @@
 ///
 /// `Color` automatically uses the `color!` or `gradient!` macro's syntax.
 ///
+/// * Animate
+///
+/// Creates an [`Animator`](crate::anim::Animator) that moves the other `Interpolate`s,
+/// the root has to be `sequence`, `parallel`, `repeat` or `stagger`.
+///
+/// ```
+/// transition!(
+///     Opacity 0.3 Linear default 0.0;
+///     Offset 0.3 Linear default [0, -20];
+///     animate sequence(
+///         delay 0.5,
+///         parallel(
+///             Opacity 0.3 Linear to 1.0,
+///             Offset 0.5 CubicOut to [0, 0],
+///         ),
+///         repeat 3 (Offset 0.2 Linear to [0, 4], Offset 0.2 Linear to [0, 0]),
+///     );
+/// )
+/// ```
+///
+/// Steps are written as `Field seconds Easing to value`.
+/// `stagger seconds (..)` plays its content on each child instead,
+/// starting `seconds` after the previous child.
+///
 #[macro_export]
 macro_rules! transition {
     ($($tt:tt)*) => {
@@ -60,10 +93,139 @@ macro_rules! easing {
     ])};
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! animation_impl {
+    ({$($out: expr),*}) => {[$($out),*]};
+    ({$($out: expr),*} delay $time:tt $(, $($rest:tt)*)?) => {
+        $crate::animation_impl!({
+            $($out,)*
+            $crate::anim::Animation::delay($time as f32)
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} sequence ($($inner:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::animation_impl!({
+            $($out,)*
+            $crate::anim::Animation::sequence($crate::animation_impl!({} $($inner)*))
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} parallel ($($inner:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::animation_impl!({
+            $($out,)*
+            $crate::anim::Animation::parallel($crate::animation_impl!({} $($inner)*))
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} repeat $count:tt ($($inner:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::animation_impl!({
+            $($out,)*
+            $crate::anim::Animation::sequence($crate::animation_impl!({} $($inner)*))
+                .repeat($count as usize)
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} stagger $time:tt ($($inner:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::animation_impl!({
+            $($out,)*
+            $crate::anim::Animation::sequence($crate::animation_impl!({} $($inner)*))
+                .stagger($time as f32)
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} Color $time:tt $ease:tt to ($value:expr) $(, $($rest:tt)*)?) => {
+        $crate::animation_impl!({
+            $($out,)*
+            $crate::anim::Animation::to::<$crate::bevy::prelude::Color>(
+                $value,
+                $crate::easing!($ease),
+                $time as f32
+            )
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} Color $time:tt $ease:tt to $value:tt $(, $($rest:tt)*)?) => {
+        $crate::animation_impl!({
+            $($out,)*
+            $crate::anim::Animation::to::<$crate::bevy::prelude::Color>(
+                $crate::color!($value),
+                $crate::easing!($ease),
+                $time as f32
+            )
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} $name:ident $time:tt $ease:tt to $value:expr $(, $($rest:tt)*)?) => {
+        $crate::animation_impl!({
+            $($out,)*
+            $crate::anim::Animation::to::<$name>(
+                $crate::util::DslInto::dinto($value),
+                $crate::easing!($ease),
+                $time as f32
+            )
+        }
+        $($($rest)*)?)
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! transition_impl {
     ({$($out: expr),*}) => {($($out),*)};
+    ({$($out: expr),*} animate $kind:ident ($($inner:tt)*) $(;$($rest:tt)*)?) => {
+        $crate::transition_impl!({
+            $($out,)*
+            $crate::anim::Animator::new({
+                let [animation] = $crate::animation_impl!({} $kind ($($inner)*));
+                animation
+            })
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} animate $kind:ident $arg:tt ($($inner:tt)*) $(;$($rest:tt)*)?) => {
+        $crate::transition_impl!({
+            $($out,)*
+            $crate::anim::Animator::new({
+                let [animation] = $crate::animation_impl!({} $kind $arg ($($inner)*));
+                animation
+            })
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} Color spring($stiffness:expr, $damping:expr $(, $mass:expr)?) default ($value:expr) $(;$($rest:tt)*)?) => {
+        $crate::transition_impl!({
+            $($out,)*
+            $crate::anim::Interpolate::<$crate::bevy::prelude::Color>::spring(
+                $crate::anim::Spring::new($stiffness as f32, $damping as f32)
+                    $(.with_mass($mass as f32))?,
+                $value
+            )
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} Color spring($stiffness:expr, $damping:expr $(, $mass:expr)?) default $value:tt $(;$($rest:tt)*)?) => {
+        $crate::transition_impl!({
+            $($out,)*
+            $crate::anim::Interpolate::<$crate::bevy::prelude::Color>::spring(
+                $crate::anim::Spring::new($stiffness as f32, $damping as f32)
+                    $(.with_mass($mass as f32))?,
+                $crate::color!($value)
+            )
+        }
+        $($($rest)*)?)
+    };
+    ({$($out: expr),*} $name:ident spring($stiffness:expr, $damping:expr $(, $mass:expr)?) default $value:expr $(;$($rest:tt)*)?) => {
+        $crate::transition_impl!({
+            $($out,)*
+            $crate::anim::Interpolate::<$name>::spring(
+                $crate::anim::Spring::new($stiffness as f32, $damping as f32)
+                    $(.with_mass($mass as f32))?,
+                $crate::util::DslInto::dinto($value)
+            )
+        }
+        $($($rest)*)?)
+    };
     ({$($out: expr),*} Color $time:tt $ease:tt default ($value:expr) $(;$($rest:tt)*)?) => {
         $crate::transition_impl!({
             $($out,)*
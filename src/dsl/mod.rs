@@ -22,7 +22,7 @@ mod clipping;
 //mod rich_text;
 
 
-pub use util::{OneOrTwo, Scale, Aspect, WidgetWrite, ParentAnchor};
+pub use util::{OneOrTwo, Scale, Aspect, WidgetWrite, ParentAnchor, SpacialConst};
 pub use crate::util::convert::{OptionEx, DslFromOptionEx, IntoAsset};
 #[doc(hidden)]
 pub use itertools::izip;
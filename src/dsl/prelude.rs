@@ -15,7 +15,7 @@ pub const EPS: f32 = f32::EPSILON;
 pub use bevy::prelude::Color;
 pub use crate::{Transform2D, Hitbox, Dimension, Opacity, Detach, SizeUnit, Size2};
 pub use crate::layout::LayoutControl::{Linebreak, IgnoreLayout};
-pub use crate::anim::{Interpolate, Offset, Rotation, Scale, Index};
+pub use crate::anim::{Interpolate, Offset, Rotation, Scale, Index, Margin, Padding, Easing, Timeline, Track, TimelineMarker, Animation, Animator, InterpolationFinished, DespawnOnFinish, AnimationTimeControl, TimeSource};
 pub use interpolation::EaseFunction;
 
 /// Return this inside `AsyncSystem` functions.
@@ -37,6 +37,8 @@ pub use crate::widgets::{
     },
     constraints::{PositionFac, SharedPosition},
     scroll::{Scrolling, ScrollParent},
+    virtual_list::{VirtualList, RowHeight},
+    list::{ListBinding, ListItems},
     drag::Dragging,
     inputbox::InputOverflow
 };
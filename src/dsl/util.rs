@@ -63,6 +63,7 @@ impl<T> DslFrom<T> for Option<LayoutObject> where T: Layout {
 ///
 /// Note `Left` can be used as `CenterLeft`, etc.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpacialConst {
     TopLeft,
     TopCenter,
@@ -60,11 +60,7 @@ impl Widget for InputBoxBuilder {
                 color: self.color.expect("color is required."),
                 anchor: Anchor::CENTER_LEFT,
                 extra: InputBoxText,
-                extra: TextFragment {
-                    text: self.text,
-                    font,
-                    size: 0.0
-                }
+                extra: TextFragment::new(self.text).with_font(font)
             })
         );
         let bar = commands.entity(self.cursor_bar.expect("cursor_bar is required."))
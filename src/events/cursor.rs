@@ -1,12 +1,16 @@
 use std::mem::discriminant;
 
-use bevy::{ecs::{system::{Query, SystemParam, Res}, query::{With, Without}, component::Component, bundle::Bundle}, render::{camera::Camera, view::Visibility}, transform::components::GlobalTransform, reflect::Reflect, math::Vec2};
+use bevy::{ecs::{system::{Query, SystemParam, Res}, query::{Has, With, Without}, component::Component, bundle::Bundle}, render::{camera::Camera, view::Visibility}, transform::components::GlobalTransform, reflect::Reflect, math::Vec2};
+use bevy::ecs::entity::Entity;
+use bevy::hierarchy::Parent;
+use bevy::render::camera::NormalizedRenderTarget;
 use bevy::window::{CursorIcon, Window, PrimaryWindow};
-use crate::{Transform2D, util::convert::DslInto, Size2, DimensionData, RectrayRem};
+use crate::{Transform2D, util::convert::DslInto, Size2, DimensionData, RectrayRem, RectrayRoot, RootEntity, RootRects};
+use crate::core::root::{centered_rect, renders_to_window, window_camera};
 
 use crate::widgets::clipping::CameraClip;
 
-use super::RectrayCamera;
+use super::{CursorDetectionItem, RectrayCamera};
 
 
 /// Displays only when the window's CursorIcon is this.
@@ -43,62 +47,188 @@ impl TrackCursor {
 /// A query that finds a camera used for cursor handling.
 #[derive(Debug, SystemParam)]
 pub struct CameraQuery<'w, 's> {
-    marked_camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<RectrayCamera>>,
-    unmarked_camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform), (Without<RectrayCamera>, Without<CameraClip>)>,
+    marked_camera: Query<'w, 's, (Entity, &'static Camera, &'static GlobalTransform), With<RectrayCamera>>,
+    unmarked_camera: Query<'w, 's, (Entity, &'static Camera, &'static GlobalTransform), (Without<RectrayCamera>, Without<CameraClip>)>,
 }
 
 impl CameraQuery<'_, '_> {
+    /// Find the camera used for cursor handling.
+    pub fn get(&self) -> Option<(&Camera, &GlobalTransform)> {
+        match self.marked_camera.get_single() {
+            Ok((_, cam, transform)) => Some((cam, transform)),
+            Err(_) => self.unmarked_camera.get_single().ok().map(|(_, cam, transform)| (cam, transform)),
+        }
+    }
+
+    /// Find the entity of the camera used for cursor handling.
+    pub fn entity(&self) -> Option<Entity> {
+        match self.marked_camera.get_single() {
+            Ok((entity, ..)) => Some(entity),
+            Err(_) => self.unmarked_camera.get_single().ok().map(|(entity, ..)| entity),
+        }
+    }
+
     pub fn viewport_to_world(&self, pos: Vec2) -> Option<Vec2> {
-        let(camera, camera_transform) = match self.marked_camera.get_single() {
-            Ok((cam, transform)) => (cam, transform),
-            Err(_) => match self.unmarked_camera.get_single(){
-                Ok((cam, transform)) => (cam, transform),
-                Err(_) => return None,
-            },
-        };
+        let (camera, camera_transform) = self.get()?;
         camera
             .viewport_to_world(camera_transform, pos)
             .map(|ray| ray.origin.truncate())
     }
 }
 
-pub fn custom_cursor_controller(
-    windows: Query<&Window, With<PrimaryWindow>>,
-    mut query: Query<(&CustomCursor, &mut Visibility)>
-) {
-    let Ok(window) = windows.get_single() else { return };
-
-    if window.cursor_position().is_some() {
-        for (cursor, mut vis) in query.iter_mut() {
-            if discriminant(&cursor.0) == discriminant(&window.cursor.icon) {
-                *vis = Visibility::Inherited;
-            } else {
-                *vis = Visibility::Hidden;
+/// A query that maps cursor positions between windows and the world space of [`RectrayRoot`]s.
+///
+/// Entities without a [`RectrayRoot`] are displayed by the camera of [`CameraQuery`].
+#[derive(SystemParam)]
+pub struct RootCursor<'w, 's> {
+    camera: CameraQuery<'w, 's>,
+    cameras: Query<'w, 's, (Entity, &'static Camera, &'static GlobalTransform)>,
+    windows: Query<'w, 's, (Entity, &'static Window, Has<PrimaryWindow>)>,
+    parents: Query<'w, 's, &'static Parent>,
+    root_entities: Query<'w, 's, &'static RootEntity>,
+    roots: Query<'w, 's, &'static RectrayRoot>,
+}
+
+impl RootCursor<'_, '_> {
+    fn primary(&self) -> Option<Entity> {
+        self.windows.iter().find(|(.., primary)| *primary).map(|(entity, ..)| entity)
+    }
+
+    /// Find the window containing the cursor and the cursor's position in it,
+    /// focused windows are preferred.
+    pub fn cursor_window(&self) -> Option<(Entity, Vec2)> {
+        self.windows.iter()
+            .filter_map(|(entity, window, _)| Some((entity, window.cursor_position()?, window.focused)))
+            .max_by_key(|(.., focused)| *focused)
+            .map(|(entity, position, _)| (entity, position))
+    }
+
+    /// Find the camera used for cursor handling in a window.
+    ///
+    /// This is the camera of [`CameraQuery`] if it renders to the window,
+    /// otherwise the active camera with the highest `order` rendering to the window.
+    pub fn window_camera(&self, window: Entity) -> Option<(Entity, &Camera, &GlobalTransform)> {
+        let primary = self.primary();
+        if let Some(camera) = self.camera.entity().and_then(|x| self.cameras.get(x).ok()) {
+            if renders_to_window(camera.1, window, primary) {
+                return Some(camera);
             }
         }
-    } else {
-        for (_, mut vis) in query.iter_mut() {
-            *vis = Visibility::Hidden;
+        window_camera(self.cameras.iter(), window, primary)
+    }
+
+    /// Convert a cursor position in a window to the world space of its camera.
+    pub fn window_to_world(&self, window: Entity, position: Vec2) -> Option<Vec2> {
+        let (_, camera, transform) = self.window_camera(window)?;
+        let viewport = camera.logical_viewport_rect()?;
+        camera.viewport_to_world_2d(transform, position - viewport.min)
+    }
+
+    /// Find the [`RectrayRoot`] of an entity.
+    ///
+    /// Uses the [`RootEntity`] written during layout if present.
+    pub fn root_of(&self, mut entity: Entity) -> Option<&RectrayRoot> {
+        if let Ok(root) = self.roots.get(entity) {
+            return Some(root);
+        }
+        if let Ok(RootEntity(root)) = self.root_entities.get(entity) {
+            return self.roots.get((*root)?).ok();
+        }
+        loop {
+            if let Ok(root) = self.roots.get(entity) {
+                return Some(root);
+            }
+            entity = self.parents.get(entity).ok()?.get();
         }
     }
+
+    fn root_camera(&self, entity: Entity) -> Option<(Entity, &Camera, &GlobalTransform)> {
+        match self.root_of(entity) {
+            None | Some(RectrayRoot::Follow { .. } | RectrayRoot::Projected { .. })
+                => self.cameras.get(self.camera.entity()?).ok(),
+            Some(RectrayRoot::Camera(camera)) => self.cameras.get(*camera).ok(),
+            Some(RectrayRoot::Window(window)) => self.window_camera(*window),
+            Some(RectrayRoot::Image(_)) => None,
+        }
+    }
+
+    /// Find the window an entity is displayed in, `None` if rooted to an image.
+    pub fn window_of(&self, entity: Entity) -> Option<Entity> {
+        let (_, camera, _) = self.root_camera(entity)?;
+        match camera.target.normalize(self.primary())? {
+            NormalizedRenderTarget::Window(window) => Some(window.entity()),
+            _ => None,
+        }
+    }
+
+    /// Map a world space position of the camera of `window`
+    /// to the world space of an entity's root.
+    ///
+    /// Returns `None` if the entity is not displayed at this position.
+    pub fn map(&self, window: Entity, entity: Entity, pos: Vec2) -> Option<Vec2> {
+        let (base_entity, base, base_transform) = self.window_camera(window)?;
+        if self.roots.is_empty() && self.camera.entity() == Some(base_entity) {
+            return Some(pos);
+        }
+        let (root_entity, camera, transform) = self.root_camera(entity)?;
+        if root_entity == base_entity {
+            return Some(pos);
+        }
+        let primary = self.primary();
+        if camera.target.normalize(primary) != base.target.normalize(primary) {
+            return None;
+        }
+        let screen = base.world_to_viewport(base_transform, pos.extend(0.0))?
+            + base.logical_viewport_rect()?.min;
+        let viewport = camera.logical_viewport_rect()?;
+        if !viewport.contains(screen) {
+            return None;
+        }
+        camera.viewport_to_world_2d(transform, screen - viewport.min)
+    }
+
+    /// Check if the cursor at `pos` in `window` is in bounds of a widget.
+    pub fn contains(&self, window: Entity, entity: Entity, detection: &CursorDetectionItem, pos: Vec2) -> bool {
+        self.map(window, entity, pos).is_some_and(|pos| detection.contains(pos))
+    }
+}
+
+pub fn custom_cursor_controller(
+    cursor: RootCursor,
+    windows: Query<&Window>,
+    mut query: Query<(Entity, &CustomCursor, &mut Visibility)>
+) {
+    let current = cursor.cursor_window()
+        .and_then(|(entity, _)| Some((entity, windows.get(entity).ok()?.cursor.icon)));
+    for (entity, custom, mut vis) in query.iter_mut() {
+        *vis = match current {
+            Some((window, icon)) if cursor.window_of(entity) == Some(window)
+                && discriminant(&custom.0) == discriminant(&icon) => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
 }
 
 pub fn track_cursor(
     rem: Res<RectrayRem>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    camera: CameraQuery,
-    mut query: Query<(&TrackCursor, &mut Transform2D, &DimensionData)>
+    cursor: RootCursor,
+    rects: RootRects,
+    primary: Query<&Window, With<PrimaryWindow>>,
+    mut query: Query<(Entity, &TrackCursor, &mut Transform2D, &DimensionData)>
 ) {
-    let Ok(window) = windows.get_single() else { return };
-    let Some(mouse_pos) = window.cursor_position()
-        .and_then(|cursor| camera.viewport_to_world(cursor))
-    else {return};
-    let dim = Vec2::new(window.width(), window.height());
-    for (cursor, mut transform, dimension) in query.iter_mut() {
-        transform.offset = (-dim * transform.get_parent_anchor()
-            //- dimension.size * transform.anchor
-            + cursor.0.as_pixels(dim, dimension.em, rem.get())
-            + mouse_pos
-        ).into()
+    let Some((window, position)) = cursor.cursor_window() else {return};
+    let Some(mouse_pos) = cursor.window_to_world(window, position) else {return};
+    let default_root = primary.get_single().ok()
+        .map(|window| centered_rect(Vec2::new(window.width(), window.height())));
+    for (entity, track, mut transform, dimension) in query.iter_mut() {
+        let Some(pos) = cursor.map(window, entity, mouse_pos) else {continue};
+        let root = match cursor.root_of(entity) {
+            Some(root) => rects.get(root),
+            None => default_root,
+        };
+        let Some((rect, dim)) = root else {continue};
+        let offset = Vec2::from_angle(-rect.rotation)
+            .rotate(pos - rect.anchor(transform.get_parent_anchor()));
+        transform.offset = (offset + track.0.as_pixels(dim, dimension.em, rem.get())).into()
     }
 }
@@ -31,6 +31,13 @@
 //! * `CursorClickOutside`: Mouse up outside of the sprite's boundary.
 //! * `MouseWheelAction`: Stores the value of mouse wheel scrolling.
 //!
+//! # Multiple Windows
+//!
+//! Events are generated in the window containing the cursor, focused windows are preferred.
+//! Entities with a [`RectrayRoot`](crate::RectrayRoot) receive events through the camera of their root,
+//! other entities through the camera of [`CameraQuery`],
+//! mark it with [`RectrayCamera`] if there are multiple cameras.
+//!
 //! # What about Keyboard Events or Joysticks?
 //!
 //! We provide abstractions that you can use for other types of input,
@@ -55,7 +62,7 @@ pub use state::*;
 use systems::*;
 pub use wheel::{MovementUnits, ScrollScaling, MouseWheelAction};
 pub use cursor::{CustomCursor, TrackCursor};
-pub use cursor::CameraQuery;
+pub use cursor::{CameraQuery, RootCursor};
 pub use gbb::{GreaterBoundingBox, GreaterBoundingBoxPercent, GreaterBoundingBoxPx};
 pub use focus::*;
 
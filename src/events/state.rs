@@ -25,6 +25,7 @@ impl DoubleClickThreshold {
 #[derive(Debug, Resource, Reflect)]
 pub struct CursorState{
     pub(super) last_lmb_down_time: [f32; 2],
+    pub(super) window: Option<Entity>,
     pub(super) cursor_pos: Vec2,
     pub(super) up_pos: Vec2,
     pub(super) down_pos: Vec2,
@@ -41,6 +42,7 @@ impl Default for CursorState {
     fn default() -> Self {
         Self {
             last_lmb_down_time: [0.0, 0.0],
+            window: None,
             cursor_pos: Vec2::ZERO,
             up_pos: Vec2::ZERO,
             down_pos: Vec2::ZERO,
@@ -109,6 +111,11 @@ impl CursorState {
         self.cursor_pos
     }
 
+    /// The window the cursor was last in.
+    pub fn window(&self) -> Option<Entity> {
+        self.window
+    }
+
     pub fn dragging(&self) -> bool {
         self.dragging
     }
@@ -1,4 +1,4 @@
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
 
 use crate::widgets::util::OptionDo;
 
@@ -20,8 +20,7 @@ pub fn mouse_button_input(
     time: Res<Time>,
     double_click: Res<DoubleClickThreshold>,
     buttons: Res<ButtonInput<MouseButton>>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    camera: CameraQuery,
+    roots: RootCursor,
     query: Query<(Entity, &EventFlags, CursorDetection, ActiveDetection)>,
 ) {
     let iter = |f: EventFlags|query.iter().filter_map(move |(entity, flag, cursor, detection)| {
@@ -34,10 +33,10 @@ pub fn mouse_button_input(
     state.caught = false;
     state.focused = None;
     if state.blocked { return; }
-    let Ok(window) = windows.get_single() else { return };
-    let Some(mouse_pos) = window.cursor_position()
-        .and_then(|cursor| camera.viewport_to_world(cursor))
+    let Some((window, mouse_pos)) = roots.cursor_window()
+        .and_then(|(window, cursor)| Some((window, roots.window_to_world(window, cursor)?)))
     else {return;};
+    state.window = Some(window);
     state.cursor_pos = mouse_pos;
     if state.dragging {
         state.caught = true;
@@ -56,12 +55,12 @@ pub fn mouse_button_input(
                 state.drag_target = None;
                 let dragged_id = entity.id();
                 iter(EventFlags::Drop)
-                    .filter(|(.., hitbox)| hitbox.contains(mouse_pos))
+                    .filter(|(entity, _, hitbox)| roots.contains(window, *entity, hitbox, mouse_pos))
                     .max_by(|(.., a), (.., b)| a.z().total_cmp(&b.z()))
                     .exec_with(|(entity, ..)| commands.entity(entity).insert(CursorAction(EventFlags::Drop)).end());
                 iter(EventFlags::ClickOutside)
                     .filter(|(e, ..)| e != &dragged_id)
-                    .filter(|(.., hitbox)| !hitbox.contains(mouse_pos))
+                    .filter(|(entity, _, hitbox)| !roots.contains(window, *entity, hitbox, mouse_pos))
                     .for_each(|(entity, ..)| commands.entity(entity).insert(CursorClickOutside).end());
             } else {
                 if state.drag_button != MouseButton::Left && buttons.just_pressed(MouseButton::Left) {
@@ -89,7 +88,7 @@ pub fn mouse_button_input(
             state.last_lmb_down_time = [last, time.elapsed_seconds()];
         }
         if let Some((entity, flag)) = iter(EventFlags::LeftDrag|EventFlags::LeftClick)
-                .filter(|(.., hitbox)| hitbox.contains(mouse_pos))
+                .filter(|(entity, _, hitbox)| roots.contains(window, *entity, hitbox, mouse_pos))
                 .max_by(|(.., a), (.., b)| a.compare(b))
                 .map(|(entity, flags, _)| (entity, flags)
             ) {
@@ -117,7 +116,7 @@ pub fn mouse_button_input(
             state.down_pos = mouse_pos
         }
         if let Some((entity, flag)) = iter(EventFlags::RightDrag|EventFlags::RightClick)
-            .filter(|(.., hitbox)| hitbox.contains(mouse_pos))
+            .filter(|(entity, _, hitbox)| roots.contains(window, *entity, hitbox, mouse_pos))
             .max_by(|(.., a), (.., b)| a.compare(b))
             .map(|(entity, flags, _)| (entity, flags)
         ) {
@@ -144,7 +143,7 @@ pub fn mouse_button_input(
             state.down_pos = mouse_pos
         }
         if let Some((entity, flag)) = iter(EventFlags::MidDrag|EventFlags::MidClick)
-            .filter(|(.., hitbox)| hitbox.contains(mouse_pos))
+            .filter(|(entity, _, hitbox)| roots.contains(window, *entity, hitbox, mouse_pos))
             .max_by(|(.., a), (.., b)| a.compare(b))
             .map(|(entity, flags, _)| (entity, flags)
         ) {
@@ -171,7 +170,7 @@ pub fn mouse_button_input(
         if buttons.just_released(MouseButton::Left) {
             let down = state.down_pos;
             iter(EventFlags::LeftClick)
-                .filter(|(.., hitbox)| hitbox.contains(mouse_pos) && hitbox.contains(down))
+                .filter(|(entity, _, hitbox)| roots.contains(window, *entity, hitbox, mouse_pos) && roots.contains(window, *entity, hitbox, down))
                 .max_by(|(.., a), (.., b)| a.compare(b))
                 .map(|(entity, flags, _)|
                     if flags.contains(EventFlags::DoubleClick) && time.elapsed_seconds() - state.last_lmb_down_time[0] <= double_click.get() {
@@ -185,21 +184,21 @@ pub fn mouse_button_input(
         } else if buttons.just_released(MouseButton::Right) {
             let down = state.down_pos;
             iter(EventFlags::RightClick)
-                .filter(|(.., hitbox)| hitbox.contains(mouse_pos) && hitbox.contains(down))
+                .filter(|(entity, _, hitbox)| roots.contains(window, *entity, hitbox, mouse_pos) && roots.contains(window, *entity, hitbox, down))
                 .max_by(|(.., a), (.., b)| a.compare(b))
                 .map(|(entity, ..)| commands.entity(entity).insert(CursorAction(EventFlags::RightClick)).end())
                 .exec(|| state.caught = true);
         } else if buttons.just_released(MouseButton::Middle) {
             let down = state.down_pos;
             iter(EventFlags::MidClick)
-                .filter(|(.., hitbox)| hitbox.contains(mouse_pos) && hitbox.contains(down))
+                .filter(|(entity, _, hitbox)| roots.contains(window, *entity, hitbox, mouse_pos) && roots.contains(window, *entity, hitbox, down))
                 .max_by(|(.., a), (.., b)| a.compare(b))
                 .map(|(entity, ..)| commands.entity(entity).insert(CursorAction(EventFlags::MidClick)).end())
                 .exec(|| state.caught = true);
         }
         if state.focused.is_none() {
             iter(EventFlags::Hover)
-                .filter(|(.., hitbox)| hitbox.contains(mouse_pos))
+                .filter(|(entity, _, hitbox)| roots.contains(window, *entity, hitbox, mouse_pos))
                 .max_by(|(.., a), (.., b)| a.compare(b))
                 .map(|(entity, ..)| {
                     commands.entity(entity).insert(CursorFocus(EventFlags::Hover)).end();
@@ -1,10 +1,8 @@
-use bevy::{ecs::{component::Component, system::{Resource, Local, Res}}, input::mouse::{MouseWheel, MouseScrollUnit}, math::{Vec2, IVec2}, reflect::Reflect, render::camera::Camera, transform::components::GlobalTransform, window::{Window, PrimaryWindow}};
-use bevy::ecs::{system::{Query, Commands}, event::EventReader, query::{With, Without}, entity::Entity};
+use bevy::{ecs::{component::Component, system::{Resource, Local, Res}}, input::mouse::{MouseWheel, MouseScrollUnit}, math::{Vec2, IVec2}, reflect::Reflect};
+use bevy::ecs::{system::{Query, Commands}, event::EventReader, entity::Entity};
 use bevy_defer::signals::SignalId;
 
-use crate::widgets::clipping::CameraClip;
-
-use super::{EventFlags, RectrayCamera, CursorDetection, ActiveDetection};
+use super::{EventFlags, CursorDetection, ActiveDetection, RootCursor};
 
 
 
@@ -58,26 +56,16 @@ impl SignalId for MouseWheelAction {
 pub(crate) fn mousewheel_event(
     mut commands: Commands,
     scaling: Res<ScrollScaling>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    marked_camera: Query<(&Camera, &GlobalTransform), With<RectrayCamera>>,
-    unmarked_camera: Query<(&Camera, &GlobalTransform), (Without<RectrayCamera>, Without<CameraClip>)>,
+    roots: RootCursor,
     query: Query<(Entity, &EventFlags, ActiveDetection, CursorDetection)>,
     mut lines: Local<Vec2>,
     mut reader: EventReader<MouseWheel>,
 ) {
-    let(camera, camera_transform) = match marked_camera.get_single() {
-        Ok((cam, transform)) => (cam, transform),
-        Err(_) => match unmarked_camera.get_single(){
-            Ok((cam, transform)) => (cam, transform),
-            Err(_) => return,
-        },
-    };
-    let Ok(window) = windows.get_single() else { return };
-    let Some(mouse_pos) = window.cursor_position()
-        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
-        .map(|ray| ray.origin.truncate()) else {return;};
+    let Some((window, mouse_pos)) = roots.cursor_window()
+        .and_then(|(window, cursor)| Some((window, roots.window_to_world(window, cursor)?)))
+    else {return;};
     if let Some(entity) = query.iter()
-        .filter(|(_, flags, active, hitbox)| flags.contains(EventFlags::MouseWheel) && active.is_active() && hitbox.contains(mouse_pos))
+        .filter(|(entity, flags, active, hitbox)| flags.contains(EventFlags::MouseWheel) && active.is_active() && roots.contains(window, *entity, hitbox, mouse_pos))
         .max_by(|(.., a), (.., b)| a.compare(b))
         .map(|(entity,..)| entity) {
 
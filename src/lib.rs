@@ -9,6 +9,13 @@ pub mod dsl;
 pub mod widgets;
 pub mod events;
 pub mod anim;
+pub mod style;
+#[cfg(feature="asset")]
+pub mod asset;
+#[cfg(feature="debug")]
+pub mod debug;
+#[cfg(feature="testing")]
+pub mod testing;
 
 //pub mod signals;
 pub use core::*;
@@ -41,7 +48,10 @@ impl bevy::prelude::Plugin for RectrayPlugin {
             .add_plugins(events::CursorEventsPlugin)
             .add_plugins(anim::AnimationPlugin)
             .add_plugins(widgets::WidgetsPlugin)
+            .add_plugins(style::StylePlugin)
             .add_plugins(bevy_defer::DefaultAsyncPlugin)
         ;
+        #[cfg(feature="asset")]
+        app.add_plugins(asset::UiAssetPlugin);
     }
 }
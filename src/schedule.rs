@@ -1,12 +1,13 @@
 //! `SystemSets` for `bevy_rectray`.
 
 use bevy::input::InputSystem;
+use bevy::render::camera::CameraUpdateSystem;
 use bevy::text::update_text2d_layout;
 use bevy::transform::systems::{propagate_transforms, sync_simple_transforms};
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 
-use crate::RectrayRem;
+use crate::{PixelSnapping, RectrayRem, update_ui_scaling, hide_projected_roots};
 
 use crate::core::pipeline::{compute_aoui_transforms, compute_aoui_opacity};
 use crate::core::systems::*;
@@ -60,6 +61,7 @@ impl bevy::prelude::Plugin for CorePlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app
             .init_resource::<RectrayRem>()
+            .init_resource::<PixelSnapping>()
             .configure_sets(PreUpdate, EventSet.after(InputSystem))
             .add_systems(PreUpdate, bevy::ecs::prelude::apply_deferred
                 .after(EventSet)
@@ -78,6 +80,7 @@ impl bevy::prelude::Plugin for CorePlugin {
                 .before(PipelineSet)
                 .after(update_text2d_layout))
             .configure_sets(PostUpdate, PipelineSet
+                .after(CameraUpdateSystem)
                 .before(StoreOutputSet))
             .configure_sets(PostUpdate, StoreOutputSet
                 .before(propagate_transforms)
@@ -88,6 +91,7 @@ impl bevy::prelude::Plugin for CorePlugin {
                 .after(sync_simple_transforms)
             )
             .add_systems(PostUpdate, (
+                update_ui_scaling,
                 set_occluded,
                 copy_anchor,
                 copy_anchor_sprite,
@@ -107,6 +111,9 @@ impl bevy::prelude::Plugin for CorePlugin {
                 sync_opacity_sprite,
                 sync_opacity_text,
             ).in_set(StoreOutputSet))
+            .add_systems(PostUpdate, hide_projected_roots
+                .after(sync_opacity_vis)
+                .in_set(StoreOutputSet))
             .add_systems(PostUpdate, (
                 build_mesh_2d_global_transform,
                 build_global_transform
@@ -0,0 +1,307 @@
+//! Optional named styles for `bevy_rectray`.
+//!
+//! `bevy_rectray` ships no widget styles, but a [`Theme`] can be used to
+//! share colors, fonts, sizes and sprites between widgets.
+//!
+//! ```
+//! commands.insert_resource(Theme::new()
+//!     .with("panel", Style {
+//!         color: Some(color!(darkgray)),
+//!         font: Some(font.clone()),
+//!         ..Default::default()
+//!     })
+//!     .with("title", Style {
+//!         font_size: Some(FontSize::Ems(2.0)),
+//!         ..Default::default()
+//!     })
+//! );
+//!
+//! frame!(commands {
+//!     style: "panel",
+//!     child: text! {
+//!         style: "title",
+//!         text: "Hello",
+//!     }
+//! });
+//! ```
+//!
+//! # Inheritance
+//!
+//! Fields not set in a style are inherited from the closest styled ancestor,
+//! similar to how [`FontSize::None`] inherits `em`. In the example above
+//! `title` inherits `color` and `font` from `panel`.
+//!
+//! Like in CSS, unstyled descendants of a styled entity inherit `color`, `font`
+//! and `transition`, `font_size` is already inherited through `em`.
+//! `sprite` and `hitbox` only apply to styled entities.
+//!
+//! # Theme Switching
+//!
+//! Styles are re-applied to all styled entities and their descendants when the [`Theme`] changes,
+//! through [`Coloring`], [`TextFragment`], [`Dimension`] and other components.
+//! Otherwise only subtrees with a changed [`Styled`] or `Parent` are re-applied.
+//!
+//! Note values set in a style overwrite values set on the widget.
+
+use bevy::app::{Plugin, Update};
+use bevy::asset::Handle;
+use bevy::ecs::change_detection::DetectChanges;
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::{Entity, EntityHashSet};
+use bevy::ecs::query::{Changed, Or, With};
+use bevy::ecs::removal_detection::RemovedComponents;
+use bevy::ecs::system::{Commands, Local, Query, Res, Resource};
+use bevy::ecs::world::Ref;
+use bevy::hierarchy::{Children, Parent};
+use bevy::render::color::Color;
+use bevy::render::texture::Image;
+use bevy::text::{Font, Text};
+use bevy::utils::HashMap;
+
+use crate::anim::{Easing, Interpolate};
+use crate::widgets::TextFragment;
+use crate::{Coloring, Dimension, FontSize, Hitbox};
+
+/// Transition preset of a [`Style`], applied through [`Interpolate<Color>`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StyleTransition {
+    pub easing: Easing,
+    pub time: f32,
+}
+
+/// A named style entry in a [`Theme`], unset fields are inherited.
+#[derive(Debug, Clone, Default)]
+pub struct Style {
+    /// Sets [`Coloring`].
+    pub color: Option<Color>,
+    /// Sets the font of [`TextFragment`], [`Text`] or `Handle<Font>`.
+    pub font: Option<Handle<Font>>,
+    /// Sets the `font_size` of [`Dimension`].
+    pub font_size: Option<FontSize>,
+    /// Sets `Handle<Image>`.
+    pub sprite: Option<Handle<Image>>,
+    /// Sets [`Hitbox`].
+    pub hitbox: Option<Hitbox>,
+    /// If set, interpolate to the new color.
+    pub transition: Option<StyleTransition>,
+}
+
+impl Style {
+    /// Fill unset fields with values from `parent`.
+    pub fn inherit(&self, parent: &Style) -> Style {
+        Style {
+            color: self.color.or(parent.color),
+            font: self.font.clone().or_else(|| parent.font.clone()),
+            font_size: self.font_size.or(parent.font_size),
+            sprite: self.sprite.clone().or_else(|| parent.sprite.clone()),
+            hitbox: self.hitbox.or(parent.hitbox),
+            transition: self.transition.or(parent.transition),
+        }
+    }
+
+    /// Fields inherited by unstyled descendants.
+    fn unstyled(&self) -> Style {
+        Style {
+            color: self.color,
+            font: self.font.clone(),
+            transition: self.transition,
+            ..Default::default()
+        }
+    }
+}
+
+/// A collection of named [`Style`]s.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct Theme {
+    styles: HashMap<String, Style>,
+}
+
+impl Theme {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a named style.
+    pub fn with(mut self, name: impl Into<String>, style: Style) -> Self {
+        self.styles.insert(name.into(), style);
+        self
+    }
+
+    /// Add or replace a named style.
+    pub fn insert(&mut self, name: impl Into<String>, style: Style) {
+        self.styles.insert(name.into(), style);
+    }
+
+    /// Obtain a named style.
+    pub fn get(&self, name: &str) -> Option<&Style> {
+        self.styles.get(name)
+    }
+
+    /// Obtain a named style mutably.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Style> {
+        self.styles.get_mut(name)
+    }
+
+    /// Replace all styles, this re-applies styles on all styled entities.
+    pub fn switch(&mut self, other: Theme) {
+        *self = other;
+    }
+}
+
+/// Apply a named [`Style`] from the [`Theme`] to this entity.
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct Styled(pub String);
+
+impl Styled {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+}
+
+/// Resolve the style of an entity, including fields inherited from ancestors.
+fn resolve_style(
+    theme: &Theme,
+    entity: Entity,
+    query: &Query<(Option<&Styled>, Option<&Parent>)>,
+) -> Style {
+    let Ok((styled, parent)) = query.get(entity) else {return Style::default()};
+    let inherited = match parent {
+        Some(parent) => resolve_style(theme, parent.get(), query),
+        None => Style::default(),
+    };
+    match styled.and_then(|x| theme.get(&x.0)) {
+        Some(style) => style.inherit(&inherited),
+        None => inherited,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn apply_styles(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    styled: Query<Entity, With<Styled>>,
+    changed: Query<Entity, Or<(Changed<Styled>, Changed<Parent>)>>,
+    mut removed: RemovedComponents<Styled>,
+    hierarchy: Query<(Option<&Styled>, Option<&Parent>)>,
+    children: Query<&Children>,
+    mut query: Query<(
+        Option<Ref<Styled>>,
+        Option<Ref<Parent>>,
+        Option<&mut Coloring>,
+        Option<&mut Interpolate<Color>>,
+        Option<&mut TextFragment>,
+        Option<&mut Text>,
+        Option<&mut Handle<Font>>,
+        Option<&mut Handle<Image>>,
+        Option<&mut Dimension>,
+        Option<&mut Hitbox>,
+    )>,
+    mut visited: Local<EntityHashSet>,
+) {
+    let roots: Vec<Entity> = if theme.is_changed() {
+        styled.iter().collect()
+    } else {
+        changed.iter().chain(removed.read()).collect()
+    };
+    if roots.is_empty() {
+        return;
+    }
+    visited.clear();
+    let mut stack: Vec<(Entity, Style)> = roots.into_iter()
+        .map(|entity| {
+            let inherited = match hierarchy.get(entity) {
+                Ok((_, Some(parent))) => resolve_style(&theme, parent.get(), &hierarchy),
+                _ => Style::default(),
+            };
+            (entity, inherited)
+        })
+        .collect();
+    while let Some((entity, inherited)) = stack.pop() {
+        if !visited.insert(entity) {
+            continue;
+        }
+        let Ok((styled, parent, coloring, interpolate, fragment, text, font_handle, image, dimension, hitbox))
+            = query.get_mut(entity) else {continue};
+        let resolved = match styled.as_ref().and_then(|x| theme.get(&x.0)) {
+            Some(style) => style.inherit(&inherited),
+            None => inherited,
+        };
+        if let Ok(children) = children.get(entity) {
+            stack.extend(children.iter().map(|child| (*child, resolved.clone())));
+        }
+        let style = match &styled {
+            Some(_) => resolved,
+            None => resolved.unstyled(),
+        };
+        // Do not play transitions on newly spawned widgets.
+        let added = match &styled {
+            Some(styled) => styled.is_added(),
+            None => parent.is_some_and(|x| x.is_added()),
+        };
+        let transition = if added {None} else {style.transition};
+        if let Some(color) = style.color {
+            match (interpolate, transition) {
+                (Some(mut interpolate), None) if added => interpolate.set(color),
+                (Some(mut interpolate), _) => interpolate.interpolate_to(color),
+                (None, Some(StyleTransition { easing, time })) if coloring.is_some() => {
+                    let current = coloring.as_ref().map(|x| x.color).unwrap_or(color);
+                    let mut interpolate = Interpolate::<Color>::new(easing, current, time);
+                    interpolate.interpolate_to(color);
+                    commands.entity(entity).insert(interpolate);
+                },
+                (None, _) => if let Some(mut coloring) = coloring {
+                    if coloring.color != color {
+                        coloring.color = color;
+                    }
+                },
+            }
+        }
+        if let Some(font) = &style.font {
+            if let Some(mut fragment) = fragment {
+                TextFragment::set_font(&mut fragment, font);
+            }
+            if let Some(mut text) = text {
+                if text.sections.iter().any(|x| &x.style.font != font) {
+                    text.sections.iter_mut().for_each(|x| x.style.font = font.clone());
+                }
+            }
+            if let Some(mut handle) = font_handle {
+                if handle.as_ref() != font {
+                    *handle = font.clone();
+                }
+            }
+        }
+        if let Some(sprite) = &style.sprite {
+            if let Some(mut image) = image {
+                if image.as_ref() != sprite {
+                    *image = sprite.clone();
+                }
+            }
+        }
+        if let Some(font_size) = style.font_size {
+            if let Some(mut dimension) = dimension {
+                if dimension.font_size != font_size {
+                    dimension.font_size = font_size;
+                }
+            }
+        }
+        match (style.hitbox, hitbox) {
+            (Some(value), Some(mut hitbox)) => if *hitbox != value {
+                *hitbox = value;
+            },
+            (Some(value), None) => {
+                commands.entity(entity).insert(value);
+            },
+            (None, _) => (),
+        }
+    }
+}
+
+pub(crate) struct StylePlugin;
+
+impl Plugin for StylePlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<Theme>()
+            .add_systems(Update, apply_styles);
+    }
+}
@@ -0,0 +1,422 @@
+use std::any::type_name;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::app::{App, PostUpdate};
+use bevy::asset::{AssetApp, AssetPlugin};
+use bevy::ecs::entity::Entity;
+use bevy::ecs::query::{Has, With};
+use bevy::ecs::system::SystemState;
+use bevy::ecs::world::World;
+use bevy::hierarchy::HierarchyPlugin;
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::input::keyboard::{Key, KeyCode, KeyboardInput, NativeKey};
+use bevy::input::mouse::{MouseButton, MouseButtonInput, MouseScrollUnit, MouseWheel};
+use bevy::math::Vec2;
+use bevy::render::deterministic::DeterministicRenderingConfig;
+use bevy::render::camera::{camera_system, Camera, ManualTextureViews, NormalizedRenderTarget, OrthographicProjection, RenderTarget};
+use bevy::render::mesh::Mesh;
+use bevy::render::texture::Image;
+use bevy::render::view::VisibilityPlugin;
+use bevy::sprite::TextureAtlasLayout;
+use bevy::text::Font;
+use bevy::time::TimeUpdateStrategy;
+use bevy::transform::components::{GlobalTransform, Transform};
+use bevy::transform::TransformPlugin;
+use bevy::window::{PrimaryWindow, ReceivedCharacter, Window, WindowPlugin, WindowRef, WindowResolution};
+use bevy::MinimalPlugins;
+use bevy_defer::signals::{SignalId, Signals, TypedSignal};
+use parking_lot::Mutex;
+
+use crate::events::RectrayCamera;
+use crate::util::RCommands;
+use crate::{RectrayPlugin, RotatedRect};
+
+/// Target of a simulated cursor, an entity's center or a point in world space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CursorTarget {
+    Entity(Entity),
+    Point(Vec2),
+}
+
+impl From<Entity> for CursorTarget {
+    fn from(value: Entity) -> Self {
+        CursorTarget::Entity(value)
+    }
+}
+
+impl From<Vec2> for CursorTarget {
+    fn from(value: Vec2) -> Self {
+        CursorTarget::Point(value)
+    }
+}
+
+impl From<[f32; 2]> for CursorTarget {
+    fn from(value: [f32; 2]) -> Self {
+        CursorTarget::Point(value.into())
+    }
+}
+
+/// Records values sent through a signal, obtained by [`TestApp::probe`].
+///
+/// Signals only keep the latest value, so values are recorded once per frame.
+#[derive(Debug)]
+pub struct SignalProbe<T: SignalId> {
+    values: Arc<Mutex<Vec<T::Data>>>,
+}
+
+impl<T: SignalId> Clone for SignalProbe<T> {
+    fn clone(&self) -> Self {
+        Self { values: self.values.clone() }
+    }
+}
+
+impl<T: SignalId> SignalProbe<T> {
+    /// All values received.
+    pub fn values(&self) -> Vec<T::Data> {
+        self.values.lock().clone()
+    }
+
+    /// Remove and return all values received.
+    pub fn take(&self) -> Vec<T::Data> {
+        std::mem::take(&mut self.values.lock())
+    }
+
+    /// The last value received.
+    pub fn last(&self) -> Option<T::Data> {
+        self.values.lock().last().cloned()
+    }
+
+    /// Number of values received.
+    pub fn count(&self) -> usize {
+        self.values.lock().len()
+    }
+
+    /// Assert the signal has been sent, returns the last value.
+    pub fn assert_sent(&self) -> T::Data {
+        match self.last() {
+            Some(value) => value,
+            None => panic!("Signal {} has not been sent.", type_name::<T>()),
+        }
+    }
+
+    /// Assert the last value sent equals `value`.
+    pub fn assert_last(&self, value: T::Data) where T::Data: PartialEq {
+        assert_eq!(self.last(), Some(value), "Unexpected value of signal {}.", type_name::<T>());
+    }
+
+    /// Assert the signal has not been sent.
+    pub fn assert_not_sent(&self) {
+        let values = self.values();
+        assert!(values.is_empty(), "Signal {} has been sent with {:?}.", type_name::<T>(), values);
+    }
+}
+
+/// A minimal headless [`App`] with [`RectrayPlugin`], a fake window and a [`RectrayCamera`],
+/// for simulating user input.
+///
+/// Each frame advances time by `1/60` seconds.
+///
+/// ```
+/// use bevy_rectray::{testing::TestApp, dsl::prelude::*};
+/// let mut app = TestApp::new();
+/// let button = app.spawn(|commands| button!(commands {
+///     dimension: [100, 40],
+///     event: EventFlags::LeftClick,
+/// }));
+/// let clicked = app.probe::<ButtonClick>(button);
+/// app.click(button);
+/// clicked.assert_sent();
+/// ```
+pub struct TestApp {
+    pub app: App,
+    window: Entity,
+    probes: Vec<Box<dyn FnMut() + Send + Sync>>,
+}
+
+impl std::fmt::Debug for TestApp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestApp")
+            .field("window", &self.window)
+            .field("probes", &self.probes.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for TestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestApp {
+    /// Create a [`TestApp`] with a `800x600` window.
+    pub fn new() -> Self {
+        Self::with_size(800.0, 600.0)
+    }
+
+    /// Create a [`TestApp`] with a window of a specific size.
+    pub fn with_size(width: f32, height: f32) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+                MinimalPlugins,
+                AssetPlugin::default(),
+                HierarchyPlugin,
+                TransformPlugin,
+                InputPlugin,
+                VisibilityPlugin,
+                WindowPlugin {
+                    primary_window: Some(Window {
+                        resolution: WindowResolution::new(width, height),
+                        ..Default::default()
+                    }),
+                    exit_condition: bevy::window::ExitCondition::DontExit,
+                    close_when_requested: false,
+                },
+            ))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / 60.0)))
+            .init_asset::<Image>()
+            .init_asset::<Font>()
+            .init_asset::<Mesh>()
+            .init_asset::<TextureAtlasLayout>()
+            .init_resource::<ManualTextureViews>()
+            .init_resource::<DeterministicRenderingConfig>()
+            .add_systems(PostUpdate, camera_system::<OrthographicProjection>)
+            .add_plugins(RectrayPlugin);
+        app.world.spawn((
+            RectrayCamera,
+            Camera::default(),
+            OrthographicProjection::default(),
+            Transform::default(),
+            GlobalTransform::default(),
+        ));
+        let window = app.world.query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+        let mut result = Self { app, window, probes: Vec::new() };
+        result.update();
+        result
+    }
+
+    pub fn world(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    /// The window receiving input, the fake primary window by default.
+    pub fn window(&self) -> Entity {
+        self.window
+    }
+
+    /// Spawn widgets with [`RCommands`], then run the schedules once to compute layouts.
+    pub fn spawn<T>(&mut self, f: impl FnOnce(&mut RCommands) -> T) -> T {
+        let mut state = SystemState::<RCommands>::new(&mut self.app.world);
+        let mut commands = state.get_mut(&mut self.app.world);
+        let result = f(&mut commands);
+        state.apply(&mut self.app.world);
+        self.update();
+        result
+    }
+
+    /// Run the schedules once and record signals.
+    pub fn update(&mut self) -> &mut Self {
+        self.app.update();
+        self.probes.iter_mut().for_each(|f| f());
+        self
+    }
+
+    /// Run the schedules `count` times.
+    pub fn step(&mut self, count: usize) -> &mut Self {
+        for _ in 0..count {
+            self.update();
+        }
+        self
+    }
+
+    /// Find the world space position of a [`CursorTarget`].
+    pub fn position_of(&self, target: impl Into<CursorTarget>) -> Vec2 {
+        match target.into() {
+            CursorTarget::Point(point) => point,
+            CursorTarget::Entity(entity) => match self.app.world.get::<RotatedRect>(entity) {
+                Some(rect) => rect.center(),
+                None => panic!("Entity {:?} has no RotatedRect.", entity),
+            }
+        }
+    }
+
+    /// Spawn another window with a camera rendering to it, then run the schedules.
+    ///
+    /// Use [`RectrayRoot::Window`](crate::RectrayRoot::Window) to lay out widgets in it,
+    /// and [`TestApp::set_window`] to move the cursor to it.
+    pub fn spawn_window(&mut self, width: f32, height: f32) -> Entity {
+        let window = self.app.world.spawn(Window {
+            resolution: WindowResolution::new(width, height),
+            ..Default::default()
+        }).id();
+        self.app.world.spawn((
+            Camera {
+                target: RenderTarget::Window(WindowRef::Entity(window)),
+                ..Default::default()
+            },
+            OrthographicProjection::default(),
+            Transform::default(),
+            GlobalTransform::default(),
+        ));
+        self.update();
+        window
+    }
+
+    /// Move the cursor to another window, removing it from the current one.
+    ///
+    /// Input events are sent to this window afterwards.
+    pub fn set_window(&mut self, window: Entity) -> &mut Self {
+        if let Some(mut current) = self.app.world.get_mut::<Window>(self.window) {
+            current.set_cursor_position(None);
+        }
+        self.window = window;
+        self
+    }
+
+    /// Move the cursor in world space without running the schedules.
+    fn set_cursor(&mut self, target: impl Into<CursorTarget>) {
+        let position = self.position_of(target);
+        let window = self.window;
+        let world = &mut self.app.world;
+        let primary = world.query_filtered::<Entity, With<PrimaryWindow>>().get_single(world).ok();
+        // The `RectrayCamera` if it renders to the window, otherwise the camera with the highest order.
+        let viewport = world.query::<(&Camera, &GlobalTransform, Has<RectrayCamera>)>()
+            .iter(world)
+            .filter(|(camera, ..)| match camera.target.normalize(primary) {
+                Some(NormalizedRenderTarget::Window(target)) => target.entity() == window,
+                _ => false,
+            })
+            .max_by_key(|(camera, _, marked)| (*marked, camera.order))
+            .and_then(|(camera, transform, _)| Some(
+                camera.world_to_viewport(transform, position.extend(0.0))?
+                    + camera.logical_viewport_rect()?.min
+            ));
+        if let Some(mut window) = world.get_mut::<Window>(window) {
+            window.set_cursor_position(viewport);
+        }
+    }
+
+    fn send_mouse(&mut self, button: MouseButton, state: ButtonState) {
+        let window = self.window;
+        self.app.world.send_event(MouseButtonInput { button, state, window });
+    }
+
+    /// Move the cursor to a target, then run the schedules.
+    pub fn move_cursor(&mut self, target: impl Into<CursorTarget>) -> &mut Self {
+        self.set_cursor(target);
+        self.update()
+    }
+
+    /// Press a mouse button, then run the schedules.
+    pub fn mouse_down(&mut self, button: MouseButton) -> &mut Self {
+        self.send_mouse(button, ButtonState::Pressed);
+        self.update()
+    }
+
+    /// Release a mouse button, then run the schedules.
+    pub fn mouse_up(&mut self, button: MouseButton) -> &mut Self {
+        self.send_mouse(button, ButtonState::Released);
+        self.update()
+    }
+
+    /// Left click on a target.
+    pub fn click(&mut self, target: impl Into<CursorTarget>) -> &mut Self {
+        self.move_cursor(target)
+            .mouse_down(MouseButton::Left)
+            .mouse_up(MouseButton::Left)
+    }
+
+    /// Drag with the left mouse button from one target to another in 8 steps.
+    pub fn drag(&mut self, from: impl Into<CursorTarget>, to: impl Into<CursorTarget>) -> &mut Self {
+        const STEPS: usize = 8;
+        let from = self.position_of(from);
+        let to = self.position_of(to);
+        self.move_cursor(from).mouse_down(MouseButton::Left);
+        for i in 1..=STEPS {
+            self.move_cursor(from.lerp(to, i as f32 / STEPS as f32));
+        }
+        self.mouse_up(MouseButton::Left)
+    }
+
+    /// Scroll the mouse wheel vertically by lines at the current cursor position.
+    pub fn scroll(&mut self, lines: f32) -> &mut Self {
+        let window = self.window;
+        self.app.world.send_event(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.0,
+            y: lines,
+            window,
+        });
+        self.update()
+    }
+
+    /// Type characters, then run the schedules.
+    pub fn type_text(&mut self, text: &str) -> &mut Self {
+        let window = self.window;
+        for c in text.chars() {
+            self.app.world.send_event(ReceivedCharacter {
+                window,
+                char: c.to_string().into(),
+            });
+        }
+        self.update()
+    }
+
+    /// Press and release a key.
+    ///
+    /// Like `winit`, `Backspace`, `Delete`, `Enter` and `Tab` also send their control characters.
+    pub fn press(&mut self, key: KeyCode) -> &mut Self {
+        let window = self.window;
+        let char = match key {
+            KeyCode::Backspace => Some("\x08"),
+            KeyCode::Delete => Some("\x7f"),
+            KeyCode::Enter | KeyCode::NumpadEnter => Some("\r"),
+            KeyCode::Tab => Some("\t"),
+            _ => None,
+        };
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            self.app.world.send_event(KeyboardInput {
+                key_code: key,
+                logical_key: Key::Unidentified(NativeKey::Unidentified),
+                state,
+                window,
+            });
+            if let (ButtonState::Pressed, Some(char)) = (state, char) {
+                self.app.world.send_event(ReceivedCharacter { window, char: char.into() });
+            }
+            self.update();
+        }
+        self
+    }
+
+    /// Record values sent by the sender of signal `T` on an entity.
+    ///
+    /// If the entity has no sender of `T`, a new sender is added.
+    ///
+    /// # Panics
+    ///
+    /// If the entity does not exist.
+    pub fn probe<T: SignalId>(&mut self, entity: Entity) -> SignalProbe<T> {
+        let Some(mut entity_mut) = self.app.world.get_entity_mut(entity) else {
+            panic!("Entity {:?} does not exist.", entity)
+        };
+        if !entity_mut.contains::<Signals>() {
+            entity_mut.insert(Signals::new());
+        }
+        let mut signals = entity_mut.get_mut::<Signals>().expect("Signals is inserted.");
+        if !signals.has_sender::<T>() {
+            signals.add_sender::<T>(TypedSignal::new());
+        }
+        let signal = signals.senders[&std::any::TypeId::of::<T>()].clone();
+        let probe = SignalProbe::<T> { values: Default::default() };
+        let values = probe.values.clone();
+        self.probes.push(Box::new(move || {
+            if let Some(value) = signal.try_read().and_then(|x| x.get::<T::Data>()) {
+                values.lock().push(value);
+            }
+        }));
+        probe
+    }
+}
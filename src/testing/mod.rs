@@ -0,0 +1,31 @@
+//! Utilities for testing without a window, requires feature `testing`.
+//!
+//! # Layout Snapshots
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_rectray::{Dimension, bundles::RectrayBundle, testing::*};
+//! let mut world = World::new();
+//! world.spawn((
+//!     Name::new("root"),
+//!     RectrayBundle {
+//!         dimension: Dimension::pixels(Vec2::new(100.0, 100.0)),
+//!         ..Default::default()
+//!     }
+//! ));
+//! compute_layout(&mut world, Vec2::new(800.0, 600.0));
+//! assert_snapshot("tests/snapshots/root.txt", &dump_layout(&mut world));
+//! ```
+//!
+//! Since no text layout is run, use `DimensionType::Owned` for text in tests.
+//!
+//! # Simulating Input
+//!
+//! [`TestApp`] runs [`RectrayPlugin`](crate::RectrayPlugin) with a fake window,
+//! and simulates clicks, drags, scrolling and typing.
+//! Use [`TestApp::probe`] to assert on signals like `ButtonClick`, `TextChange` and `ToggleChange`.
+
+mod snapshot;
+mod harness;
+pub use harness::{TestApp, CursorTarget, SignalProbe};
+pub use snapshot::{FixedRoot, LayoutSnapshot, compute_layout, snapshot_layout, dump_layout, dump_layout_json, assert_snapshot};
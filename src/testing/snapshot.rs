@@ -0,0 +1,205 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use bevy::core::Name;
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::Entity;
+use bevy::ecs::query::{With, Without};
+use bevy::ecs::system::RunSystemOnce;
+use bevy::ecs::world::World;
+use bevy::hierarchy::{Children, Parent};
+use bevy::math::{Affine2, Vec2};
+
+use crate::layout::Container;
+use crate::{compute_aoui_opacity, compute_aoui_transforms, DimensionData, Opacity, RootQuery, RotatedRect};
+
+/// A stand-in root rectangle of a fixed size, replacing the `PrimaryWindow`.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct FixedRoot(pub Vec2);
+
+impl<'t> RootQuery<'t> for FixedRoot {
+    type Query = &'t FixedRoot;
+    type ReadOnly = ();
+
+    fn as_rect(query: &bevy::ecs::system::Query<Self::Query, Self::ReadOnly>) -> (RotatedRect, Vec2) {
+        let dim = match query.get_single() {
+            Ok(root) => root.0,
+            Err(_) => return Default::default(),
+        };
+        (RotatedRect {
+            affine: Affine2::from_scale(dim),
+            rotation: 0.0,
+            scale: Vec2::ONE,
+            z: 0.0,
+        }, dim)
+    }
+}
+
+/// Run `compute_aoui_transforms` and `compute_aoui_opacity` on a headless [`World`],
+/// with a root rectangle of size `size`.
+pub fn compute_layout(world: &mut World, size: Vec2) {
+    let mut roots = world.query_filtered::<&mut FixedRoot, ()>();
+    match roots.get_single_mut(world) {
+        Ok(mut root) => root.0 = size,
+        Err(_) => {
+            world.spawn(FixedRoot(size));
+        }
+    }
+    world.run_system_once(compute_aoui_transforms::<FixedRoot>);
+    world.run_system_once(compute_aoui_opacity);
+}
+
+/// Round to 2 decimal places for stable output.
+fn round(value: f32) -> f32 {
+    let value = (value * 100.0).round() / 100.0;
+    if value == 0.0 {0.0} else {value}
+}
+
+/// Remove module paths from type names in `Debug` output.
+fn strip_paths(debug: &str) -> String {
+    let mut out = String::new();
+    let mut segment = String::new();
+    let mut chars = debug.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            segment.clear();
+        } else if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else {
+            out.push_str(&segment);
+            segment.clear();
+            out.push(c);
+        }
+    }
+    out.push_str(&segment);
+    out
+}
+
+/// Computed layout of an entity and its descendants.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutSnapshot {
+    pub name: Option<String>,
+    pub center: [f32; 2],
+    pub size: [f32; 2],
+    pub rotation: f32,
+    pub z: f32,
+    pub em: f32,
+    pub opacity: f32,
+    /// `Debug` output of the [`Layout`](crate::layout::Layout) without module paths, if a [`Container`].
+    pub layout: Option<String>,
+    pub children: Vec<LayoutSnapshot>,
+}
+
+impl LayoutSnapshot {
+    /// Snapshot an entity and its descendants, returns `None` if not a rectray entity.
+    pub fn of(world: &World, entity: Entity) -> Option<Self> {
+        let entity = world.get_entity(entity)?;
+        let rect = entity.get::<RotatedRect>()?;
+        let data = entity.get::<DimensionData>();
+        let size = data.map(|x| x.size * rect.scale).unwrap_or(Vec2::ZERO);
+        Some(Self {
+            name: entity.get::<Name>().map(|x| x.to_string()),
+            center: rect.center().to_array().map(round),
+            size: size.to_array().map(round),
+            rotation: round(rect.rotation),
+            z: round(rect.z),
+            em: round(data.map(|x| x.em).unwrap_or(0.0)),
+            opacity: round(entity.get::<Opacity>().map(|x| x.computed_opacity).unwrap_or(1.0)),
+            layout: entity.get::<Container>().map(|x| strip_paths(&format!("{:?}", &*x.layout))),
+            children: entity.get::<Children>()
+                .into_iter()
+                .flatten()
+                .filter_map(|child| LayoutSnapshot::of(world, *child))
+                .collect(),
+        })
+    }
+
+    fn write_text(&self, depth: usize, out: &mut String) {
+        let _ = writeln!(out,
+            "{:indent$}{} [center: ({}, {}), size: ({}, {}), rotation: {}, z: {}, em: {}, opacity: {}{}]",
+            "",
+            self.name.as_deref().unwrap_or("<unnamed>"),
+            self.center[0], self.center[1],
+            self.size[0], self.size[1],
+            self.rotation,
+            self.z,
+            self.em,
+            self.opacity,
+            match &self.layout {
+                Some(layout) => format!(", layout: {}", layout),
+                None => String::new(),
+            },
+            indent = depth * 2,
+        );
+        for child in &self.children {
+            child.write_text(depth + 1, out);
+        }
+    }
+
+    /// Dump as indented text, one entity per line.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        self.write_text(0, &mut out);
+        out
+    }
+}
+
+/// Snapshot all root entities ordered by [`Entity`].
+pub fn snapshot_layout(world: &mut World) -> Vec<LayoutSnapshot> {
+    let mut roots: Vec<_> = world.query_filtered::<Entity, (With<RotatedRect>, Without<Parent>)>()
+        .iter(world)
+        .collect();
+    roots.sort();
+    roots.into_iter()
+        .filter_map(|entity| LayoutSnapshot::of(world, entity))
+        .collect()
+}
+
+/// Dump the computed layout of all root entities as stable text.
+pub fn dump_layout(world: &mut World) -> String {
+    snapshot_layout(world).iter().map(|x| x.to_text()).collect()
+}
+
+/// Dump the computed layout of all root entities as JSON.
+pub fn dump_layout_json(world: &mut World) -> String {
+    serde_json::to_string_pretty(&snapshot_layout(world)).unwrap_or_default()
+}
+
+/// Compare `actual` with the snapshot at `path`.
+///
+/// If the environment variable `RECTRAY_UPDATE_SNAPSHOTS` is set,
+/// the snapshot is written instead.
+///
+/// # Panics
+///
+/// If the snapshot is missing or does not match.
+pub fn assert_snapshot(path: impl AsRef<Path>, actual: &str) {
+    let path = path.as_ref();
+    if std::env::var_os("RECTRAY_UPDATE_SNAPSHOTS").is_some() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).expect("Could not create snapshot directory.");
+        }
+        std::fs::write(path, actual).expect("Could not write snapshot.");
+        return;
+    }
+    if !path.exists() {
+        panic!("Snapshot {} missing, set RECTRAY_UPDATE_SNAPSHOTS to write it.", path.display());
+    }
+    let expected = std::fs::read_to_string(path).expect("Could not read snapshot.");
+    if expected == actual {
+        return;
+    }
+    let mut message = format!("Snapshot {} does not match.\n", path.display());
+    for (line, (e, a)) in expected.lines().zip(actual.lines()).enumerate() {
+        if e != a {
+            let _ = write!(message, "First difference at line {}:\n- {}\n+ {}\n", line + 1, e, a);
+            break;
+        }
+    }
+    let _ = write!(message, "Expected {} lines, found {} lines.\n\
+        Set RECTRAY_UPDATE_SNAPSHOTS to update.",
+        expected.lines().count(), actual.lines().count());
+    panic!("{}", message);
+}
@@ -1,5 +1,8 @@
-use bevy::{window::CursorIcon, app::App, math::Vec2};
+use std::any::TypeId;
+use bevy::{window::CursorIcon, app::{App, Update}, math::Vec2};
+use bevy::ecs::schedule::IntoSystemConfigs;
 use crate::{widgets::util::CursorDefault, events::ScrollScaling, util::DslInto};
+use crate::widgets::list::{list_binding_system, list_resource_system, ListItem, ListBinding, ListResource, RegisteredLists};
 
 /// Extension methods to `World` and `App`
 pub trait WorldExtension {
@@ -9,6 +12,12 @@ pub trait WorldExtension {
 
     /// Register mouse wheel scrolling speed.
     fn register_scrolling_speed(&mut self, line_to_pixels: impl DslInto<Vec2>, speed: impl DslInto<Vec2>) -> &mut Self;
+
+    /// Register [`ListBinding<T>`], systems are only added once.
+    fn register_list_binding<T: ListItem>(&mut self) -> &mut Self;
+
+    /// Register a [`ListResource`] and its [`ListBinding`], systems are only added once.
+    fn register_list_resource<R: ListResource>(&mut self) -> &mut Self;
 }
 
 impl WorldExtension for App {
@@ -24,4 +33,21 @@ impl WorldExtension for App {
             pixel_scale: speed.dinto(),
         })
     }
+
+    fn register_list_binding<T: ListItem>(&mut self) -> &mut Self {
+        let mut registered = self.world.get_resource_or_insert_with(RegisteredLists::default);
+        if registered.0.insert(TypeId::of::<ListBinding<T>>()) {
+            self.add_systems(Update, list_binding_system::<T>);
+        }
+        self
+    }
+
+    fn register_list_resource<R: ListResource>(&mut self) -> &mut Self {
+        let mut registered = self.world.get_resource_or_insert_with(RegisteredLists::default);
+        if registered.0.insert(TypeId::of::<R>()) {
+            self.add_systems(Update, list_resource_system::<R>
+                .before(list_binding_system::<R::Item>));
+        }
+        self.register_list_binding::<R::Item>()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,238 @@
+use std::any::TypeId;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use bevy::ecs::entity::Entity;
+use bevy::ecs::component::Component;
+use bevy::ecs::query::Has;
+use bevy::ecs::change_detection::DetectChanges;
+use bevy::ecs::system::{Query, Res, Resource};
+use bevy::ecs::world::World;
+use bevy::hierarchy::{BuildChildren, Children};
+use bevy::time::Time;
+use bevy::utils::{HashMap, HashSet};
+use bevy_defer::signals::{SignalId, SignalReceiver};
+use crate::anim::{Easing, Interpolate};
+use crate::util::{RCommands, WidgetBuilder};
+use crate::Opacity;
+
+/// An item in a collection bound by [`ListBinding`].
+///
+/// Items are matched by key, if the key and the value are unchanged,
+/// the corresponding entity is kept, if the value changed, the entity is rebuilt.
+pub trait ListItem: Debug + Clone + PartialEq + Send + Sync + 'static {
+    type Key: Debug + Clone + Eq + Hash + Send + Sync + 'static;
+
+    fn key(&self) -> Self::Key;
+}
+
+impl<K, V> ListItem for (K, V)
+    where K: Debug + Clone + Eq + Hash + Send + Sync + 'static, V: Debug + Clone + PartialEq + Send + Sync + 'static {
+    type Key = K;
+
+    fn key(&self) -> Self::Key {
+        self.0.clone()
+    }
+}
+
+/// A [`Resource`] that can drive a [`ListBinding`].
+///
+/// Register with [`WorldExtension::register_list_resource`](crate::util::WorldExtension::register_list_resource).
+pub trait ListResource: Resource {
+    type Item: ListItem;
+
+    fn items(&self) -> &[Self::Item];
+}
+
+/// Signal for updating the items of a [`ListBinding`].
+#[derive(Debug)]
+pub struct ListItems<T: ListItem>(PhantomData<T>);
+
+impl<T: ListItem> SignalId for ListItems<T> {
+    type Data = Vec<T>;
+}
+
+/// Enter and exit transitions of a [`ListBinding`], driven by [`Interpolate<Opacity>`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ListTransition {
+    pub easing: Easing,
+    pub time: f32,
+}
+
+/// Keeps children of this entity in sync with a collection.
+///
+/// Items can be supplied through [`ListItems`] as a signal, a [`ListResource`] or
+/// [`ListBinding::set`]. Children are diffed by [`ListItem::key`] and built
+/// with a [`WidgetBuilder`], reusing entities of unchanged items.
+///
+/// This component owns all children of its entity,
+/// so it is advised to use a dedicated container.
+///
+/// # Setup Requirements
+///
+/// * Register with [`WorldExtension::register_list_binding`](crate::util::WorldExtension::register_list_binding).
+///
+/// # Transitions
+///
+/// If `transition` is set, entering children interpolates [`Opacity`] from `0` to `1`.
+/// Exiting children, including the old children of items whose value changed,
+/// are moved to the end of the container, interpolated to `0` and despawned once finished.
+#[derive(Debug, Clone, Component)]
+pub struct ListBinding<T: ListItem> {
+    builder: WidgetBuilder<T>,
+    items: Vec<(T, Entity)>,
+    pending: Option<Vec<T>>,
+    source: Option<TypeId>,
+    pub transition: Option<ListTransition>,
+}
+
+impl<T: ListItem> ListBinding<T> {
+    pub fn new(builder: WidgetBuilder<T>) -> Self {
+        Self {
+            builder,
+            items: Vec::new(),
+            pending: None,
+            source: None,
+            transition: None,
+        }
+    }
+
+    /// Initialize with a collection.
+    pub fn with_items(mut self, items: impl IntoIterator<Item = T>) -> Self {
+        self.pending = Some(items.into_iter().collect());
+        self
+    }
+
+    /// Add enter and exit transitions.
+    pub fn with_transition(mut self, easing: Easing, time: f32) -> Self {
+        self.transition = Some(ListTransition { easing, time });
+        self
+    }
+
+    /// Synchronize with a [`ListResource`].
+    pub fn from_resource<R: ListResource<Item = T>>(mut self) -> Self {
+        self.source = Some(TypeId::of::<R>());
+        self
+    }
+
+    /// Replace the underlying collection, this is applied in the next run of the system.
+    pub fn set(&mut self, items: impl IntoIterator<Item = T>) {
+        self.pending = Some(items.into_iter().collect());
+    }
+
+    /// Iterate through the current items and their entities.
+    pub fn items(&self) -> impl Iterator<Item = (&T, Entity)> + '_ {
+        self.items.iter().map(|(item, entity)| (item, *entity))
+    }
+
+    /// Obtain the entity of an item by key.
+    pub fn entity_of(&self, key: &T::Key) -> Option<Entity> {
+        self.items.iter()
+            .find(|(item, _)| &item.key() == key)
+            .map(|(_, entity)| *entity)
+    }
+}
+
+/// List bindings and resources with registered systems.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct RegisteredLists(pub(crate) HashSet<TypeId>);
+
+/// Marker for a child of a [`ListBinding`] playing its exit transition.
+#[derive(Debug, Clone, Copy, Component)]
+#[component(storage="SparseSet")]
+pub struct ListExit {
+    remaining: f32,
+}
+
+pub(crate) fn list_resource_system<R: ListResource>(
+    res: Option<Res<R>>,
+    mut query: Query<&mut ListBinding<R::Item>>,
+) {
+    let Some(res) = res else {return};
+    let id = TypeId::of::<R>();
+    for mut binding in query.iter_mut() {
+        if binding.source != Some(id) {
+            continue;
+        }
+        if res.is_changed() || binding.is_added() {
+            binding.pending = Some(res.items().to_vec());
+        }
+    }
+}
+
+pub(crate) fn list_binding_system<T: ListItem>(
+    mut commands: RCommands,
+    mut query: Query<(Entity, &mut ListBinding<T>, SignalReceiver<ListItems<T>>, Option<&Children>)>,
+    exiting: Query<Has<ListExit>>,
+) {
+    for (entity, mut binding, recv, children) in query.iter_mut() {
+        let Some(items) = recv.poll_once().or_else(|| binding.pending.take()) else {continue};
+        let binding = &mut *binding;
+        let transition = binding.transition;
+        let mut previous: HashMap<T::Key, (T, Entity)> = binding.items.drain(..)
+            .map(|(item, entity)| (item.key(), (item, entity)))
+            .collect();
+        let mut order = Vec::with_capacity(items.len());
+        let mut exits = Vec::new();
+        for item in items {
+            match previous.remove(&item.key()) {
+                Some((prev, child)) if prev == item => {
+                    binding.items.push((item, child));
+                    order.push(child);
+                },
+                replaced => {
+                    if let Some((_, child)) = replaced {
+                        exits.push(child);
+                    }
+                    let child = commands.spawn_fn(&binding.builder, item.clone());
+                    if let Some(ListTransition { easing, time }) = transition {
+                        commands.entity(child)
+                            .insert(Interpolate::<Opacity>::init(easing, (0.0, 1.0), time));
+                    }
+                    binding.items.push((item, child));
+                    order.push(child);
+                }
+            }
+        }
+        if let Some(children) = children {
+            order.extend(children.iter().filter(|x| exiting.get(**x).unwrap_or(false)));
+        }
+        exits.extend(previous.into_values().map(|(_, child)| child));
+        for child in exits {
+            match transition {
+                Some(ListTransition { easing, time }) => {
+                    commands.add_command(move |world: &mut World| {
+                        let Some(mut child) = world.get_entity_mut(child) else {return};
+                        if let Some(mut opacity) = child.get_mut::<Opacity>() {
+                            opacity.disabled = true;
+                        }
+                        if let Some(mut interpolate) = child.get_mut::<Interpolate<Opacity>>() {
+                            interpolate.interpolate_to(0.0);
+                        } else {
+                            child.insert(Interpolate::<Opacity>::init(easing, (1.0, 0.0), time));
+                        }
+                        child.insert(ListExit { remaining: time });
+                    });
+                    order.push(child);
+                }
+                None => commands.despawn(child),
+            }
+        }
+        commands.entity(entity).replace_children(&order);
+    }
+}
+
+pub(crate) fn list_exit_system(
+    time: Res<Time>,
+    mut commands: RCommands,
+    mut query: Query<(Entity, &mut ListExit)>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut exit) in query.iter_mut() {
+        exit.remaining -= delta;
+        if exit.remaining <= 0.0 {
+            commands.despawn(entity);
+        }
+    }
+}
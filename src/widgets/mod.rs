@@ -24,6 +24,13 @@
 //! | [`ScrollDiscrete`](scroll::ScrollDiscrete) | Discrete scrolling for [`Layout`](crate::layout::Layout). |
 //! | [`DragSnapBack`](drag::DragSnapBack) | Snap dragged sprite back to the source. |
 //! | [`SharedPosition`](constraints::SharedPosition) | Share position between draggable/scrollable widgets. |
+//! | [`VirtualList`](virtual_list::VirtualList) | Scrollable list that only spawns visible rows. |
+//!
+//! # Collections
+//!
+//! | Component | Description |
+//! | --------- | ----------- |
+//! | [`ListBinding`](list::ListBinding) | Keep children in sync with a keyed collection. |
 //!
 //! # Camera
 //!
@@ -58,6 +65,8 @@ pub mod inputbox;
 pub mod drag;
 pub mod richtext;
 pub mod scroll;
+pub mod virtual_list;
+pub mod list;
 pub mod clipping;
 pub mod button;
 pub mod spinner;
@@ -73,6 +82,7 @@ pub use atlas::DeferredAtlasBuilder;
 use bevy::ecs::schedule::IntoSystemConfigs;
 use bevy::app::{Plugin, PreUpdate, Update, PostUpdate, Last};
 
+use crate::core::systems::copy_dimension_sprite;
 use crate::events::{CursorAction, CursorFocus};
 use crate::schedule::{CleanupSet, LoadInputSet, PostEventSet, PostWidgetEventSet, StoreOutputSet, WidgetEventSet};
 
@@ -117,6 +127,7 @@ impl Plugin for WidgetsPlugin {
             ).in_set(PostWidgetEventSet))
             .add_systems(Update, (
                 util::set_cursor,
+                richtext::synchronize_rich_text_links,
                 util::event_conditional_visibility,
                 util::check_conditional_visibility,
                 inputbox::draw_input_box
@@ -128,6 +139,8 @@ impl Plugin for WidgetsPlugin {
                 text::sync_sprite_text_fragment,
                 spinner::spin_text_change,
                 spinner::sync_spin_text_with_text,
+                virtual_list::virtual_list_system,
+                list::list_exit_system,
                 signals::sig_set_text,
                 signals::radio_button_clear_widget,
                 signals::inputbox_clear_widget,
@@ -137,10 +150,14 @@ impl Plugin for WidgetsPlugin {
                 misc::layout_opacity_limit.pipe(misc::set_layout_opactiy_limit),
             ))
             .add_systems(PostUpdate, (
-                richtext::synchronize_glyph_spaces
+                richtext::synchronize_glyph_spaces,
+                richtext::synchronize_decoration_lines,
+                richtext::synchronize_inline_images,
+                text::copy_dimension_text_fragment.after(copy_dimension_sprite),
             ).in_set(LoadInputSet))
             .add_systems(PostUpdate, (
                 text::sync_em_text_fragment,
+                text::sync_bounds_text_fragment,
                 inputbox::sync_em_inputbox
             ).in_set(StoreOutputSet))
             .add_systems(Last, util::remove_all::<CheckButtonState>.in_set(CleanupSet))
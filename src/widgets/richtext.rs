@@ -9,8 +9,10 @@
 //! * Italics: `*text*`
 //! * Bold: `**text**`
 //! * Bold Italics: `***text***`
-//! * Underline: `__text__` (not implemented)
-//! * Strikethrough: `~~text~~` (not implemented)
+//! * Underline: `__text__`
+//! * Strikethrough: `~~text~~`
+//!
+//! A single `_` or `~` is rendered as is.
 //!
 //! Ascii whitespaces are either rendered as one space or linebreaks.
 //! Use a unicode space if you want multiple spaces. Leading and trailing
@@ -28,9 +30,9 @@
 //!
 //! Insert a new line.
 //!
-//! * `{*}` `{_}` `{~}`
+//! * `{*}` `{_}` `{~}` `{{` `{}}`
 //!
-//! Escape for '*', '_', '~'
+//! Escape for '*', '_', '~', '{', '}'. Outside of scopes `}}` is also an escape for '}'.
 //!
 //! `{left}` or `{topleft}`
 //!
@@ -53,26 +55,58 @@
 //!
 //! This sets the font size to 2 em
 //!
+//! * `{img:name}`
+//!
+//! Insert an image from the [`ImageFetcher`] set by [`RichTextBuilder::with_images`],
+//! the image is as tall as the font's ascent and sits on the baseline.
+//!
 //! * `{0}` - `{9}`
 //!
 //! Spawn an empty entity for future insertion.
 //!
+//! * `{link=id:text}`
+//!
+//! Make text segments clickable, sends `ButtonClick` with the id as a `String`
+//! to the signal set by [`RichTextBuilder::with_link_signal`].
+//! Segments of the same link are recolored together when hovered.
+//!
 //! * `{zip: {red:a}.}`
 //!
 //! Zip prevents linebreaks inside by wrapping its contents inside a `compact` layout.
 //! This is needed to preserve linebreak behavior across style groups.
 //! Changing anchor inside is unspecified behavior.
+//!
+//! # AST
+//!
+//! [`RichTextAst::parse`] parses markup into spans with resolved styles and source positions,
+//! which can be inspected, edited and spawned with [`RichTextBuilder::push_ast`].
+//! [`RichTextAst`] implements [`Display`](std::fmt::Display), which writes equivalent markup.
 
 use std::{collections::HashMap, hash::{Hash, BuildHasher}, num::ParseFloatError};
 use bevy::{reflect::Reflect, render::view::RenderLayers};
 use bevy::{asset::{Handle, Assets}, text::Font, render::color::Color, hierarchy::BuildChildren};
 use bevy::ecs::{entity::Entity, system::{Query, Res}, bundle::Bundle, component::Component};
-use crate::{Transform2D, Anchor, FontSize, Dimension, Size2, DimensionType, dimension::DimensionMut, util::RCommands};
+use bevy::hierarchy::Parent;
+use bevy::ecs::query::Without;
+use bevy::window::CursorIcon;
+use bevy::render::texture::Image;
+use bevy::sprite::{TextureAtlas, TextureAtlasLayout};
+use crate::bundles::RSpriteBundle;
+use bevy_defer::Object;
+use bevy_defer::signals::TypedSignal;
+use crate::events::{CursorFocus, EventFlags};
+use crate::util::ComposeExtension;
+use crate::widgets::button::{Button, ButtonClick, Payload};
+use crate::widgets::util::SetCursor;
+use crate::{Transform2D, Anchor, FontSize, Dimension, Size, Size2, SizeUnit, DimensionType, Coloring, dimension::DimensionMut, util::RCommands};
 use crate::layout::{Container, StackLayout};
 use crate::bundles::RectrayBundle;
 use crate::layout::LayoutControl;
 use crate::frame;
 
+mod ast;
+pub use ast::{RichTextAst, RichTextSpan, RichTextSegment, RichTextStyle, RichTextParseError};
+
 /// This widget always has the width of a space and line height of a widget.
 #[derive(Debug, Clone, Component, Default, Reflect)]
 pub struct GlyphSpace {
@@ -91,6 +125,185 @@ pub fn synchronize_glyph_spaces(mut query: Query<(&GlyphSpace, DimensionMut)>, f
     })
 }
 
+/// An image in rich text, either a whole image or a region of a texture atlas.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RichTextImage {
+    Image(Handle<Image>),
+    Atlas(Handle<Image>, Handle<TextureAtlasLayout>, usize),
+}
+
+impl From<Handle<Image>> for RichTextImage {
+    fn from(value: Handle<Image>) -> Self {
+        RichTextImage::Image(value)
+    }
+}
+
+/// Registry of images used by `{img:name}` in rich text.
+pub trait ImageFetcher {
+    fn get(&self, name: &str) -> Option<RichTextImage>;
+}
+
+impl ImageFetcher for () {
+    fn get(&self, _: &str) -> Option<RichTextImage> {
+        None
+    }
+}
+
+impl<V: Into<RichTextImage> + Clone, H: BuildHasher> ImageFetcher for HashMap<String, V, H> {
+    fn get(&self, name: &str) -> Option<RichTextImage> {
+        HashMap::get(self, name).cloned().map(Into::into)
+    }
+}
+
+impl<V: Into<RichTextImage> + Clone, H: BuildHasher> ImageFetcher for HashMap<&str, V, H> {
+    fn get(&self, name: &str) -> Option<RichTextImage> {
+        HashMap::get(self, name).cloned().map(Into::into)
+    }
+}
+
+const _: () = {
+    use bevy::utils::HashMap;
+    impl<V: Into<RichTextImage> + Clone> ImageFetcher for HashMap<String, V> {
+        fn get(&self, name: &str) -> Option<RichTextImage> {
+            HashMap::get(self, name).cloned().map(Into::into)
+        }
+    }
+
+    impl<V: Into<RichTextImage> + Clone> ImageFetcher for HashMap<&str, V> {
+        fn get(&self, name: &str) -> Option<RichTextImage> {
+            HashMap::get(self, name).cloned().map(Into::into)
+        }
+    }
+};
+
+/// An inline image in rich text, has the line height of its font,
+/// and places its sprite child on the baseline.
+#[derive(Debug, Clone, Component)]
+pub struct InlineImage {
+    font: Handle<Font>,
+    image: RichTextImage,
+    sprite: Entity,
+}
+
+pub fn synchronize_inline_images(
+    mut query: Query<(&InlineImage, DimensionMut)>,
+    mut sprites: Query<(&mut Transform2D, &mut Dimension), Without<InlineImage>>,
+    fonts: Res<Assets<Font>>,
+    images: Res<Assets<Image>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+){
+    use ab_glyph::{Font, ScaleFont};
+    query.iter_mut().for_each(|(inline, mut dimension)| {
+        let Some(font) = fonts.get(&inline.font) else { return };
+        let font = font.font.as_scaled(dimension.dynamic.em);
+        let size = match &inline.image {
+            RichTextImage::Image(image) => images.get(image).map(|x| x.size().as_vec2()),
+            RichTextImage::Atlas(_, layout, index) => layouts.get(layout)
+                .and_then(|x| x.textures.get(*index))
+                .map(|x| x.max - x.min),
+        }.unwrap_or_default();
+        let width = if size.y > 0.0 { font.ascent() * size.x / size.y } else { 0.0 };
+        dimension.source.dimension = DimensionType::Owned(Size2::pixels(width, font.height()));
+        if let Ok((mut transform, mut dimension)) = sprites.get_mut(inline.sprite) {
+            transform.offset = Size2::pixels(0.0, -font.descent());
+            dimension.dimension = DimensionType::Owned(Size2::pixels(width, font.ascent()));
+        }
+    })
+}
+
+/// An underline or a strikethrough drawn across its parent text segment.
+///
+/// Thickness and position are read from the font's metrics,
+/// the color follows the parent's [`Coloring`].
+#[derive(Debug, Clone, Component)]
+pub struct DecorationLine {
+    decoration: TextDecoration,
+    font: Handle<Font>,
+    loaded: bool,
+}
+
+impl DecorationLine {
+    pub fn new(decoration: TextDecoration, font: Handle<Font>) -> Self {
+        Self { decoration, font, loaded: false }
+    }
+}
+
+/// Read the position and thickness of a decoration from
+/// the `post` or `OS/2` table of the first font in `data`.
+fn decoration_metrics(data: &[u8], decoration: TextDecoration) -> Option<(f32, f32)> {
+    let u16_at = |at: usize| data.get(at..at + 2).map(|x| u16::from_be_bytes([x[0], x[1]]));
+    let u32_at = |at: usize| data.get(at..at + 4).map(|x| u32::from_be_bytes([x[0], x[1], x[2], x[3]]) as usize);
+    let font = if data.starts_with(b"ttcf") { u32_at(12)? } else { 0 };
+    let (tag, position, thickness) = if decoration.contains(TextDecoration::Underline) {
+        (b"post", 8, 10)
+    } else {
+        (b"OS/2", 28, 26)
+    };
+    let record = (0..u16_at(font + 4)? as usize)
+        .map(|index| font + 12 + index * 16)
+        .find(|record| data.get(*record..*record + 4) == Some(&tag[..]))?;
+    let table = u32_at(record + 8)?;
+    Some((u16_at(table + position)? as i16 as f32, u16_at(table + thickness)? as i16 as f32))
+}
+
+pub fn synchronize_decoration_lines(
+    mut query: Query<(&mut DecorationLine, &Parent, &mut Transform2D, &mut Dimension, &mut Coloring)>,
+    parents: Query<&Coloring, Without<DecorationLine>>,
+    fonts: Res<Assets<Font>>,
+){
+    use ab_glyph::Font;
+    query.iter_mut().for_each(|(mut line, parent, mut transform, mut dimension, mut coloring)| {
+        if let Ok(color) = parents.get(parent.get()) {
+            if coloring.color != color.color {
+                coloring.color = color.color;
+            }
+        }
+        if line.loaded {
+            return;
+        }
+        let Some(font) = fonts.get(&line.font) else { return };
+        let font = &font.font;
+        let (position, thickness) = match decoration_metrics(font.font_data(), line.decoration) {
+            Some(metrics) => metrics,
+            None if line.decoration.contains(TextDecoration::Underline) =>
+                (font.descent_unscaled() / 2.0, font.height_unscaled() / 20.0),
+            None => (font.ascent_unscaled() * 0.3, font.height_unscaled() / 20.0),
+        };
+        // `em` is the height of the font, the baseline is `ascent` below the top.
+        let height = font.height_unscaled();
+        transform.offset = Size2::em(0.0, (position + thickness / 2.0 - font.ascent_unscaled()) / height);
+        dimension.dimension = DimensionType::Owned(Size2::new(
+            Size::new(SizeUnit::Percent, 1.0),
+            Size::new(SizeUnit::Em, thickness / height),
+        ));
+        line.loaded = true;
+    })
+}
+
+/// A segment of a `{link=id:text}` in rich text.
+///
+/// Segments of the same link share a `group` and are highlighted together.
+#[derive(Debug, Clone, Component)]
+pub struct RichTextLink {
+    pub id: String,
+    group: Entity,
+    pub color: Color,
+    pub hover: Color,
+}
+
+pub fn synchronize_rich_text_links(mut query: Query<(&RichTextLink, Option<&CursorFocus>, Option<&mut Coloring>)>) {
+    let hovered: Vec<Entity> = query.iter()
+        .filter(|(_, focus, _)| focus.is_some_and(|x| x.intersects(EventFlags::Hover | EventFlags::LeftPressed)))
+        .map(|(link, ..)| link.group)
+        .collect();
+    query.iter_mut().for_each(|(link, _, coloring)| {
+        let Some(mut coloring) = coloring else { return };
+        let color = if hovered.contains(&link.group) { link.hover } else { link.color };
+        if coloring.color != color {
+            coloring.color = color;
+        }
+    })
+}
 
 tlbf::tlbf!(
     pub FontStyle: u8 {
@@ -99,11 +312,23 @@ tlbf::tlbf!(
     }
 );
 
+tlbf::tlbf!(
+    pub TextDecoration: u8 {
+        Underline,
+        Strikethrough,
+    }
+);
+
 impl FontStyle {
     #[allow(non_upper_case_globals)]
     pub const None: Self = Self(0);
 }
 
+impl TextDecoration {
+    #[allow(non_upper_case_globals)]
+    pub const None: Self = Self(0);
+}
+
 pub trait FontFetcher {
     fn get(&self, name: &str, style: FontStyle) -> Handle<Font>;
 
@@ -174,63 +399,23 @@ const _: () = {
 };
 
 
-enum RichTextScope {
-    Font,
-    Color,
-    Size,
-    Anchor,
-    Zip,
-}
-
-
-fn newlines(s: &str) -> usize {
-    s.chars().filter(|x| *x == '\n').count()
-}
-
-fn is_ws(s: &str) -> bool {
-    s.chars().all(|x| x.is_ascii_whitespace())
-}
-
-fn hex1(s: u8) -> Result<f32, RichTextError> {
-    Ok((
-        match s {
-            b'0'..=b'9' => s - b'0',
-            b'a'..=b'z' => s - b'a' + 10_u8,
-            _ => return Err(RichTextError::InvalidHexDigit(s))
-        } * 0x11
-    ) as f32 / 255.0)
-}
-
-fn hex2(a: u8, b: u8) -> Result<f32, RichTextError> {
-    Ok((
-        match a {
-            b'0'..=b'9' => a - b'0',
-            b'a'..=b'z' => a - b'a' + 10_u8,
-            _ => return Err(RichTextError::InvalidHexDigit(a))
-        } * 16 +
-        match b {
-            b'0'..=b'9' => b - b'0',
-            b'a'..=b'z' => b - b'a' + 10_u8,
-            _ => return Err(RichTextError::InvalidHexDigit(b))
-        }
-    ) as f32 / 255.0)
-}
-
-pub struct RichTextBuilder<'t, 'w, 's, F: FontFetcher, B: Bundle + Clone = ()>{
+pub struct RichTextBuilder<'t, 'w, 's, F: FontFetcher, B: Bundle + Clone = (), I: ImageFetcher = ()>{
     /// This will be bundled into every text children
     bundle: B,
     /// This determines the inserted `LinebreakBundle`'s height.
     line_gap:(Handle<Font>, FontSize),
     commands: &'t mut RCommands<'w, 's>,
     font: F,
-    style: FontStyle,
+    images: I,
     color_stack: Vec<Color>,
     size_stack: Vec<FontSize>,
     font_stack: Vec<String>,
     anchor_stack: Vec<Anchor>,
-    zip: Option<Vec<Entity>>,
+    /// Id and the first segment of the current link.
+    link: Option<(String, Entity)>,
+    link_signal: Option<TypedSignal<Object>>,
+    link_hover: Color,
     buffer: Vec<Entity>,
-    pop_stack: Vec<RichTextScope>,
     layer: u8,
 }
 
@@ -241,68 +426,38 @@ impl<'a, 'w, 's, F: FontFetcher> RichTextBuilder<'a, 'w, 's, F> {
             line_gap: (font.default(), FontSize::None),
             commands,
             font,
-            style: FontStyle::None,
+            images: (),
             color_stack: Vec::new(),
             size_stack: Vec::new(),
             font_stack: Vec::new(),
             anchor_stack: Vec::new(),
-            zip: None,
+            link: None,
+            link_signal: None,
+            link_hover: Color::rgb(0.5, 0.7, 1.0),
             buffer: Vec::new(),
-            pop_stack: Vec::new(),
             layer: 0,
         }
     }
 }
 
-struct FindSplit<'t, 'a> {
-    s: &'t str,
-    pat: &'a [char],
-    one: bool,
-}
-
-impl<'t> Iterator for FindSplit<'t, '_> {
-    type Item = &'t str;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.s.is_empty() {
-            return None;
-        }
-        if self.one {
-            let (result, s) = self.s.split_at(1);
-            self.s = s;
-            self.one = false;
-            Some(result)
-        } else if let Some(pos) = self.s.find(self.pat){
-            if pos == 0 {
-                let (result, s) = self.s.split_at(1);
-                self.s = s;
-                self.one = false;
-                Some(result)
-            } else {
-                let (result, s) = self.s.split_at(pos);
-                self.s = s;
-                self.one = true;
-                Some(result)
-            }
-        } else {
-            let result = self.s;
-            self.s = "";
-            Some(result)
-        }
-    }
-}
-
-impl<'a, 'w, 's, F: FontFetcher, B: Bundle + Clone> RichTextBuilder<'a, 'w, 's, F, B> {
+impl<'a, 'w, 's, F: FontFetcher, B: Bundle + Clone, I: ImageFetcher> RichTextBuilder<'a, 'w, 's, F, B, I> {
     #[must_use]
     pub fn build(self) -> Vec<Entity> {
         self.buffer
     }
 
     #[must_use]
-    pub fn with_bundle<B2: Bundle + Clone>(self, bun: B2) -> RichTextBuilder<'a, 'w, 's, F, B2>{
-        let RichTextBuilder { bundle:_, line_gap, commands, font, style, layer, color_stack, size_stack, font_stack, anchor_stack, zip, buffer, pop_stack } = self;
+    pub fn with_bundle<B2: Bundle + Clone>(self, bun: B2) -> RichTextBuilder<'a, 'w, 's, F, B2, I>{
+        let RichTextBuilder { bundle:_, line_gap, commands, font, images, layer, color_stack, size_stack, font_stack, anchor_stack, link, link_signal, link_hover, buffer } = self;
         let bundle = bun;
-        RichTextBuilder { bundle, line_gap, commands, font, style, layer, color_stack, size_stack, font_stack, anchor_stack, zip, buffer, pop_stack }
+        RichTextBuilder { bundle, line_gap, commands, font, images, layer, color_stack, size_stack, font_stack, anchor_stack, link, link_signal, link_hover, buffer }
+    }
+
+    /// Sets the registry of images used by `{img:name}`.
+    #[must_use]
+    pub fn with_images<I2: ImageFetcher>(self, images: I2) -> RichTextBuilder<'a, 'w, 's, F, B, I2>{
+        let RichTextBuilder { bundle, line_gap, commands, font, images: _, layer, color_stack, size_stack, font_stack, anchor_stack, link, link_signal, link_hover, buffer } = self;
+        RichTextBuilder { bundle, line_gap, commands, font, images, layer, color_stack, size_stack, font_stack, anchor_stack, link, link_signal, link_hover, buffer }
     }
 
     #[must_use]
@@ -342,102 +497,177 @@ impl<'a, 'w, 's, F: FontFetcher, B: Bundle + Clone> RichTextBuilder<'a, 'w, 's,
         self
     }
 
-    fn push_font(&mut self, v: String, scoped: bool) {
-        if !scoped {
-            self.font_stack.pop();
-        }
-        self.pop_stack.push(RichTextScope::Font);
-        self.font_stack.push(v);
+    /// Sets the signal `{link=id:text}` sends its id to on click.
+    #[must_use]
+    pub fn with_link_signal(mut self, signal: TypedSignal<Object>) -> Self{
+        self.link_signal = Some(signal);
+        self
     }
 
-    fn font(&self) -> &str {
-        self.font_stack.last().map(|x| x.as_str()).unwrap_or("")
+    /// Sets the color of links when hovered.
+    #[must_use]
+    pub fn with_link_hover(mut self, color: Color) -> Self{
+        self.link_hover = color;
+        self
     }
 
-    fn push_size(&mut self, v: FontSize, scoped: bool) {
-        if !scoped {
-            self.size_stack.pop();
-        }
-        self.pop_stack.push(RichTextScope::Size);
-        self.size_stack.push(v);
+    fn font(&self, style: &RichTextStyle) -> Handle<Font> {
+        let name = style.font.as_deref()
+            .or(self.font_stack.last().map(|x| x.as_str()))
+            .unwrap_or("");
+        self.font.get(name, style.font_style)
     }
 
-    fn size(&self) -> FontSize {
-        self.size_stack.last().copied().unwrap_or(FontSize::None)
+    fn size(&self, style: &RichTextStyle) -> FontSize {
+        style.size.or(self.size_stack.last().copied()).unwrap_or(FontSize::None)
     }
 
-    fn push_color(&mut self, v: Color, scoped: bool) {
-        if !scoped {
-            self.color_stack.pop();
-        }
-        self.pop_stack.push(RichTextScope::Color);
-        self.color_stack.push(v);
+    fn color(&self, style: &RichTextStyle) -> Color {
+        style.color.or(self.color_stack.last().copied()).unwrap_or(Color::WHITE)
     }
 
-    fn color(&self) -> Color {
-        self.color_stack.last().copied().unwrap_or(Color::WHITE)
+    fn anchor(&self, style: &RichTextStyle) -> Anchor {
+        style.anchor.or(self.anchor_stack.last().copied()).unwrap_or(Anchor::CENTER_LEFT)
     }
 
-    fn push_zip(&mut self) -> Result<(), RichTextError> {
-        if self.zip.is_some() {
-            return Err(RichTextError::ZipInZip);
+    /// Make a text segment part of its link, if any.
+    fn link(&mut self, entity: Entity, style: &RichTextStyle) {
+        let Some(id) = &style.link else {
+            self.link = None;
+            return;
+        };
+        let group = match &self.link {
+            Some((current, group)) if current == id => *group,
+            _ => {
+                self.link = Some((id.clone(), entity));
+                entity
+            }
+        };
+        let color = self.color(style);
+        let mut commands = self.commands.entity(entity);
+        commands.compose(EventFlags::Hover | EventFlags::LeftClick)
+            .insert((
+                crate::Hitbox::FULL,
+                Button,
+                Payload::new(id.clone()),
+                SetCursor {
+                    flags: EventFlags::Hover | EventFlags::LeftPressed,
+                    icon: CursorIcon::Pointer,
+                },
+                RichTextLink { id: id.clone(), group, color, hover: self.link_hover },
+            ));
+        if let Some(signal) = &self.link_signal {
+            commands.add_sender::<ButtonClick>(signal.clone());
         }
-        self.zip = Some(Vec::new());
-        self.pop_stack.push(RichTextScope::Zip);
-        Ok(())
     }
 
-
-    fn push_anchor(&mut self, v: Anchor, scoped: bool) {
-        if !scoped {
-            self.anchor_stack.pop();
+    /// Add lines of decorations to a text segment.
+    fn decorate(&mut self, entity: Entity, style: &RichTextStyle) {
+        for decoration in [TextDecoration::Underline, TextDecoration::Strikethrough] {
+            if !style.decoration.contains(decoration) {
+                continue;
+            }
+            let line = crate::rectangle!((self.commands) {
+                anchor: Anchor::TOP_CENTER,
+                dimension: Size2::ZERO,
+                color: self.color(style),
+                extra: DecorationLine::new(decoration, self.font(style)),
+            });
+            if self.layer != 0 {
+                self.commands.entity(line).insert(RenderLayers::layer(self.layer));
+            }
+            self.commands.entity(entity).add_child(line);
         }
-        self.pop_stack.push(RichTextScope::Anchor);
-        self.anchor_stack.push(v);
     }
 
-    fn anchor(&self) -> Anchor {
-        self.anchor_stack.last().copied().unwrap_or(Anchor::CENTER_LEFT)
+    fn spawn_image(&mut self, name: &str, style: &RichTextStyle) -> Result<Entity, RichTextError> {
+        let image = self.images.get(name).ok_or_else(|| RichTextError::ImageNotFound(name.to_owned()))?;
+        let texture = match &image {
+            RichTextImage::Image(image) | RichTextImage::Atlas(image, ..) => image.clone(),
+        };
+        let mut sprite = self.commands.spawn_bundle(RSpriteBundle {
+            transform: Transform2D::UNIT.with_anchor(Anchor::BOTTOM_CENTER),
+            dimension: Dimension {
+                dimension: DimensionType::Owned(Size2::ZERO),
+                ..Default::default()
+            },
+            texture,
+            ..Default::default()
+        });
+        if let RichTextImage::Atlas(_, layout, index) = &image {
+            sprite.insert(TextureAtlas { layout: layout.clone(), index: *index });
+        }
+        if self.layer != 0 {
+            sprite.insert(RenderLayers::layer(self.layer));
+        }
+        let sprite = sprite.id();
+        let entity = frame!((self.commands) {
+            anchor: self.anchor(style),
+            font_size: self.size(style),
+            extra: InlineImage {
+                font: self.font(style),
+                image,
+                sprite,
+            },
+        });
+        self.commands.entity(entity).add_child(sprite);
+        self.link(entity, style);
+        Ok(entity)
     }
 
     pub fn push_bundle(&mut self, bun: impl Bundle) {
-        let anchor = self.anchor();
+        let anchor = self.anchor_stack.last().copied().unwrap_or(Anchor::CENTER_LEFT);
         let entity = self.commands.spawn_bundle(bun).insert(
             Transform2D::UNIT.with_anchor(anchor)
         ).id();
         self.buffer.push(entity);
     }
 
+    /// Parse and spawn rich text markup.
     pub fn push_str(&mut self, s: &str) -> Result<(), RichTextError>{
-        use xi_unicode::LineBreakIterator;
-
-        macro_rules! spawn {
-            ($s: expr) => {
-                {
-                    let entity = $crate::text! ((self.commands) {
-                        text: $s,
-                        anchor: self.anchor(),
-                        font_size: self.size(),
-                        font: self.font.get(self.font(), self.style),
-                        color: self.color(),
-                        extra: self.bundle.clone(),
-                    });
-                    // unfortunately the macro doesn't work for this
-                    if self.layer != 0 {
-                        self.commands.entity(entity).insert(RenderLayers::layer(self.layer));
-                    }
-                    if let Some(zip) = &mut self.zip {
-                        zip.push(entity);
-                    } else {
-                        self.buffer.push(entity)
-                    };
-                }
-            };
+        let ast = RichTextAst::parse(s)?;
+        self.push_ast(&ast)
+    }
+
+    /// Spawn parsed rich text.
+    pub fn push_ast(&mut self, ast: &RichTextAst) -> Result<(), RichTextError>{
+        for span in &ast.spans {
+            if let Some(entity) = self.spawn_span(span)? {
+                self.buffer.push(entity);
+            }
         }
+        Ok(())
+    }
 
-        macro_rules! line_gap {
-            () => {
-                self.buffer.push(self.commands.spawn_bundle((
+    fn spawn_span(&mut self, span: &RichTextSpan) -> Result<Option<Entity>, RichTextError>{
+        let style = &span.style;
+        let entity = match &span.segment {
+            RichTextSegment::Text(text) => {
+                let entity = crate::text! ((self.commands) {
+                    text: text,
+                    anchor: self.anchor(style),
+                    font_size: self.size(style),
+                    font: self.font(style),
+                    color: self.color(style),
+                    extra: self.bundle.clone(),
+                });
+                // unfortunately the macro doesn't work for this
+                if self.layer != 0 {
+                    self.commands.entity(entity).insert(RenderLayers::layer(self.layer));
+                }
+                entity
+            },
+            RichTextSegment::Space => frame!((self.commands) {
+                anchor: self.anchor(style),
+                font_size: self.size(style),
+                extra: GlyphSpace {
+                    font: self.font(style),
+                },
+                extra: LayoutControl::WhiteSpace,
+            }),
+            RichTextSegment::LineBreak => {
+                self.link = None;
+                return Ok(Some(self.commands.spawn_bundle((
                     RectrayBundle{
                         dimension: Dimension {
                             font_size: self.line_gap.1,
@@ -449,205 +679,42 @@ impl<'a, 'w, 's, F: FontFetcher, B: Bundle + Clone> RichTextBuilder<'a, 'w, 's,
                     GlyphSpace {
                         font: self.line_gap.0.clone()
                     }
-                )).id())
-            };
-        }
-
-        let mut last_space = 0;
-
-        macro_rules! space {
-            () => {
-                if self.buffer.len() != last_space {
-                    last_space = self.buffer.len() + 1;
-                    let entity = frame!((self.commands) {
-                        anchor: self.anchor(),
-                        font_size: self.size(),
-                        extra: GlyphSpace {
-                            font: self.font.get(self.font(), self.style),
-                        },
-                        extra: LayoutControl::WhiteSpace,
-                    });
-                    if let Some(zip) = &mut self.zip {
-                        zip.push(entity);
-                    } else {
-                        self.buffer.push(entity)
-                    };
-                }
-            };
-        }
-        let s = s.trim();
-
-        let mut last = 0;
-        let mut iter = LineBreakIterator::new(s).map(|(next, _)| {
-            let string = &s[last..next];
-            last = next;
-            string
-        }).flat_map(|s| FindSplit {
-            s,
-            pat: &['{', '}', ':', '@', '*', '+', '#', ' ', '\n', '\t'],
-            one: false,
-        }).peekable();
-
-        while let Some(item) = iter.next() {
-            match item {
-                "{" => {
-                    let mut cc = iter.next().ok_or(RichTextError::BracketsNotClosed)?;
-                    let prefix = match cc {
-                        "{" => { spawn!("{"); continue; }
-                        "*" => Some('*'),
-                        "+" => Some('+'),
-                        "_" => Some('_'),
-                        "~" => Some('~'),
-                        "@" => Some('@'),
-                        "#" => Some('#'),
-                        _ => None,
-                    };
-                    if let Some(prefix) = prefix {
-                        cc = iter.next().ok_or(RichTextError::BracketsNotClosed)?;
-                        if cc == "}" {
-                            spawn!(prefix);
-                            break;
-                        }
-                    }
-                    let scoped = if cc.ends_with(':') {
-                        let len = cc.len();
-                        cc = &cc[..len - 1];
-                        true
-                    } else {
-                        match iter.next() {
-                            Some(":") => true,
-                            Some("}") => false,
-                            Some(cc) => return Err(RichTextError::NotColonOrEndParam(cc.to_owned())),
-                            None => return Err(RichTextError::NotColonOrEndParam("end of string.".to_owned())),
-                        }
-                    };
-                    match cc.to_lowercase().as_str() {
-                        "br" => line_gap!(),
-                        "zip" => self.push_zip()?,
-                        "left" => self.push_anchor(Anchor::CENTER_LEFT, scoped),
-                        "right" => self.push_anchor(Anchor::CENTER_RIGHT, scoped),
-                        "top" => self.push_anchor(Anchor::TOP_CENTER, scoped),
-                        "bottom" => self.push_anchor(Anchor::BOTTOM_CENTER, scoped),
-                        "center" => self.push_anchor(Anchor::CENTER, scoped),
-                        "centerleft" => self.push_anchor(Anchor::CENTER_LEFT, scoped),
-                        "centerright" => self.push_anchor(Anchor::CENTER_RIGHT, scoped),
-                        "topcenter" => self.push_anchor(Anchor::TOP_CENTER, scoped),
-                        "bottomcenter" => self.push_anchor(Anchor::BOTTOM_CENTER, scoped),
-                        "topleft" => self.push_anchor(Anchor::TOP_LEFT, scoped),
-                        "topright" => self.push_anchor(Anchor::TOP_RIGHT, scoped),
-                        "bottomleft" => self.push_anchor(Anchor::BOTTOM_LEFT, scoped),
-                        "bottomright" => self.push_anchor(Anchor::BOTTOM_RIGHT, scoped),
-                        cc => match prefix {
-                            Some('@') => self.push_font(cc.to_owned(), scoped),
-                            Some('+') => {
-                                let size = cc.parse()?;
-                                self.push_size(FontSize::Pixels(size), scoped);
-                            },
-                            Some('*') => {
-                                let size = cc.parse()?;
-                                self.push_size(FontSize::Ems(size), scoped);
-                            },
-                            Some('#') => {
-                                let b = cc.as_bytes();
-                                let color = match b {
-                                    [a,b,c] => Color::rgba_linear(hex1(*a)?, hex1(*b)?, hex1(*c)?, 1.0),
-                                    [a,b,c,d] => Color::rgba_linear(hex1(*a)?, hex1(*b)?, hex1(*c)?, hex1(*d)?),
-                                    [a,b,c,d,e,f] => Color::rgba_linear(hex2(*a, *b)?, hex2(*c, *d)?, hex2(*e, *f)?, 1.0),
-                                    [a,b,c,d,e,f,g,h] => Color::rgba_linear(hex2(*a, *b)?, hex2(*c, *d)?, hex2(*e, *f)?, hex2(*g, *h)?),
-                                    _ => return Err(RichTextError::InvalidHexColor(cc.to_owned()))
-                                };
-                                self.push_color(color, scoped);
-                            },
-                            Some(pfx) => return Err(RichTextError::UnsupportedPrefix(pfx)),
-                            None => {
-                                if let Some([r, g, b, a]) = parse_color::parse_flat_lower(cc) {
-                                    let color = Color::rgba_linear(
-                                        r as f32 / 255.0,
-                                        g as f32 / 255.0,
-                                        b as f32 / 255.0,
-                                        a as f32 / 255.0,
-                                    );
-                                    self.push_color(color, scoped);
-                                } else {
-                                    return Err(RichTextError::InvalidControlCode(cc.to_owned()))
-                                }
-                            }
-                        }
-                    }
-                }
-                "*" => {
-                    let mut flag = FontStyle::Italic;
-                    if iter.peek() == Some(&"*") {
-                        flag = FontStyle::Bold;
-                        iter.next();
-                        if iter.peek() == Some(&"*") {
-                            flag = FontStyle::Bold | FontStyle::Italic;
-                            iter.next();
-                        }
+                )).id()));
+            },
+            RichTextSegment::Image(name) => return self.spawn_image(name, style).map(Some),
+            RichTextSegment::Zip(spans) => {
+                let mut children = Vec::new();
+                for span in spans {
+                    if let Some(entity) = self.spawn_span(span)? {
+                        children.push(entity);
                     }
-                    self.style ^= flag;
                 }
-                "}" => {
-                    match iter.peek() {
-                        Some(&"}") => spawn!("}"),
-                        _ => match self.pop_stack.pop() {
-                            Some(RichTextScope::Anchor) => { self.anchor_stack.pop(); },
-                            Some(RichTextScope::Color) => { self.color_stack.pop(); },
-                            Some(RichTextScope::Font) => { self.font_stack.pop(); },
-                            Some(RichTextScope::Size) => { self.size_stack.pop(); },
-                            Some(RichTextScope::Zip) => {
-                                let anchor = self.anchor();
-                                self.buffer.push(self.commands.spawn_bundle((
-                                    RectrayBundle {
-                                        dimension: Dimension {
-                                            font_size: self.size(),
-                                            ..Default::default()
-                                        },
-                                        ..Default::default()
-                                    },
-                                    Container {
-                                        layout: StackLayout::HSTACK.into(),
-                                        margin: Size2::ZERO,
-                                        padding: Size2::ZERO,
-                                        range: Default::default(),
-                                        maximum: usize::MAX,
-                                    }
-                                ))
-                                .insert(Transform2D::UNIT.with_anchor(anchor))
-                                .push_children(&self.zip.take().ok_or(RichTextError::HierarchyMismatch)?)
-                                .id());
-                            },
-                            None => return Err(RichTextError::BracketsMismatch),
-                        }
-                    }
-                },
-                s if is_ws(s) => {
-                    let mut lines = newlines(s);
-                    loop {
-                        match iter.peek() {
-                            Some(ws) if is_ws(ws) => {
-                                lines += newlines(ws);
-                                iter.next();
-                            }
-                            Some(_) => {
-                                match lines {
-                                    0|1 => space!(),
-                                    x => for _ in 0..x-1 {
-                                        line_gap!();
-                                    }
-                                }
-                                break;
-                            }
-                            None => break,
-                        }
+                let (anchor, font_size) = (self.anchor(style), self.size(style));
+                return Ok(Some(self.commands.spawn_bundle((
+                    RectrayBundle {
+                        dimension: Dimension {
+                            font_size,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    Container {
+                        layout: StackLayout::HSTACK.into(),
+                        margin: Size2::ZERO,
+                        padding: Size2::ZERO,
+                        range: Default::default(),
+                        maximum: usize::MAX,
                     }
-                },
-                s => spawn!(s),
-            }
-        }
-        Ok(())
+                ))
+                .insert(Transform2D::UNIT.with_anchor(anchor))
+                .push_children(&children)
+                .id()));
+            },
+        };
+        self.decorate(entity, style);
+        self.link(entity, style);
+        Ok(Some(entity))
     }
-
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -668,6 +735,8 @@ pub enum RichTextError {
     InvalidHexColor(String),
     #[error("Invalid hex digit 0x{}, expected '0-9|a-f'.", 0)]
     InvalidHexDigit(u8),
+    #[error("Image {} not found.", 0)]
+    ImageNotFound(String),
     #[error("Cannot zip in a zip block")]
     ZipInZip,
     #[error("Expected ':' or '}}', found {}.", 0)]
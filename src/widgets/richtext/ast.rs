@@ -0,0 +1,723 @@
+use std::fmt::{Display, Formatter, Write};
+use std::ops::Range;
+use bevy::render::color::Color;
+use crate::{Anchor, FontSize};
+use super::{FontStyle, TextDecoration, RichTextError};
+
+/// Style of a [`RichTextSpan`], unset fields use the builder's values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichTextStyle {
+    pub font: Option<String>,
+    pub font_style: FontStyle,
+    pub size: Option<FontSize>,
+    pub color: Option<Color>,
+    pub anchor: Option<Anchor>,
+    pub decoration: TextDecoration,
+    /// Id of the link this span is part of.
+    pub link: Option<String>,
+}
+
+impl Default for RichTextStyle {
+    fn default() -> Self {
+        Self {
+            font: None,
+            font_style: FontStyle::None,
+            size: None,
+            color: None,
+            anchor: None,
+            decoration: TextDecoration::None,
+            link: None,
+        }
+    }
+}
+
+/// Content of a [`RichTextSpan`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RichTextSegment {
+    /// A text segment without whitespaces.
+    Text(String),
+    /// A space between text segments.
+    Space,
+    /// A newline.
+    LineBreak,
+    /// An image by name, from `{img:name}`.
+    Image(String),
+    /// Spans that should not be broken into multiple lines, from `{zip:...}`.
+    Zip(Vec<RichTextSpan>),
+}
+
+/// A segment of rich text with its style and position in the source string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichTextSpan {
+    pub segment: RichTextSegment,
+    pub style: RichTextStyle,
+    /// Byte range in the source string.
+    pub position: Range<usize>,
+}
+
+/// Parsed rich text markup, see the [module documentation](super) for the format.
+///
+/// Displays as markup that parses into equivalent spans, positions aside.
+/// Adjacent text spans of the same style may be merged into one span when parsed again.
+/// Since the markup has no syntax for [`FontSize::Rems`], formatting spans
+/// with that size fails with [`std::fmt::Error`].
+///
+/// ```
+/// # use bevy_rectray::widgets::richtext::RichTextAst;
+/// let ast = RichTextAst::parse("Hello, {red:**world!**}")?;
+/// assert_eq!(ast.to_string(), "Hello, {#ff0000ff:**world!**}");
+/// # Ok::<(), bevy_rectray::widgets::richtext::RichTextParseError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RichTextAst {
+    pub spans: Vec<RichTextSpan>,
+}
+
+/// A [`RichTextError`] with its byte range in the source string.
+#[derive(Debug, thiserror::Error)]
+#[error("{error} at {}..{}", position.start, position.end)]
+pub struct RichTextParseError {
+    pub position: Range<usize>,
+    #[source]
+    pub error: RichTextError,
+}
+
+impl From<RichTextParseError> for RichTextError {
+    fn from(value: RichTextParseError) -> Self {
+        value.error
+    }
+}
+
+/// Names of anchors, the first name of an anchor is used in serialization.
+const ANCHORS: [(&str, Anchor); 13] = [
+    ("left", Anchor::CENTER_LEFT),
+    ("right", Anchor::CENTER_RIGHT),
+    ("top", Anchor::TOP_CENTER),
+    ("bottom", Anchor::BOTTOM_CENTER),
+    ("center", Anchor::CENTER),
+    ("centerleft", Anchor::CENTER_LEFT),
+    ("centerright", Anchor::CENTER_RIGHT),
+    ("topcenter", Anchor::TOP_CENTER),
+    ("bottomcenter", Anchor::BOTTOM_CENTER),
+    ("topleft", Anchor::TOP_LEFT),
+    ("topright", Anchor::TOP_RIGHT),
+    ("bottomleft", Anchor::BOTTOM_LEFT),
+    ("bottomright", Anchor::BOTTOM_RIGHT),
+];
+
+enum RichTextScope {
+    Font,
+    Color,
+    Size,
+    Anchor,
+    Zip,
+    Link,
+}
+
+fn newlines(s: &str) -> usize {
+    s.chars().filter(|x| *x == '\n').count()
+}
+
+fn is_ws(s: &str) -> bool {
+    s.chars().all(|x| x.is_ascii_whitespace())
+}
+
+fn hex_digit(s: u8) -> Result<u8, RichTextError> {
+    match s {
+        b'0'..=b'9' => Ok(s - b'0'),
+        b'a'..=b'f' => Ok(s - b'a' + 10_u8),
+        _ => Err(RichTextError::InvalidHexDigit(s))
+    }
+}
+
+fn hex1(s: u8) -> Result<f32, RichTextError> {
+    Ok((hex_digit(s)? * 0x11) as f32 / 255.0)
+}
+
+fn hex2(a: u8, b: u8) -> Result<f32, RichTextError> {
+    Ok((hex_digit(a)? * 16 + hex_digit(b)?) as f32 / 255.0)
+}
+
+struct FindSplit<'t, 'a> {
+    s: &'t str,
+    pat: &'a [char],
+    one: bool,
+}
+
+impl<'t> Iterator for FindSplit<'t, '_> {
+    type Item = &'t str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.s.is_empty() {
+            return None;
+        }
+        if self.one {
+            let (result, s) = self.s.split_at(1);
+            self.s = s;
+            self.one = false;
+            Some(result)
+        } else if let Some(pos) = self.s.find(self.pat){
+            if pos == 0 {
+                let (result, s) = self.s.split_at(1);
+                self.s = s;
+                self.one = false;
+                Some(result)
+            } else {
+                let (result, s) = self.s.split_at(pos);
+                self.s = s;
+                self.one = true;
+                Some(result)
+            }
+        } else {
+            let result = self.s;
+            self.s = "";
+            Some(result)
+        }
+    }
+}
+
+struct RichTextParser {
+    color_stack: Vec<Color>,
+    size_stack: Vec<FontSize>,
+    font_stack: Vec<String>,
+    anchor_stack: Vec<Anchor>,
+    link_stack: Vec<String>,
+    pop_stack: Vec<RichTextScope>,
+    font_style: FontStyle,
+    decoration: TextDecoration,
+    /// Start position, style and spans of the current zip block.
+    zip: Option<(usize, RichTextStyle, Vec<RichTextSpan>)>,
+    spans: Vec<RichTextSpan>,
+}
+
+impl RichTextParser {
+    fn style(&self) -> RichTextStyle {
+        RichTextStyle {
+            font: self.font_stack.last().cloned(),
+            font_style: self.font_style,
+            size: self.size_stack.last().copied(),
+            color: self.color_stack.last().copied(),
+            anchor: self.anchor_stack.last().copied(),
+            decoration: self.decoration,
+            link: self.link_stack.last().cloned(),
+        }
+    }
+
+    fn spans(&mut self) -> &mut Vec<RichTextSpan> {
+        match &mut self.zip {
+            Some((_, _, spans)) => spans,
+            None => &mut self.spans,
+        }
+    }
+
+    fn push(&mut self, segment: RichTextSegment, position: Range<usize>) {
+        let style = self.style();
+        self.spans().push(RichTextSpan { segment, style, position })
+    }
+
+    /// Push a space unless at the start or after another space.
+    fn push_space(&mut self, position: Range<usize>) {
+        match self.spans().last() {
+            None | Some(RichTextSpan { segment: RichTextSegment::Space, .. }) => (),
+            Some(_) => self.push(RichTextSegment::Space, position),
+        }
+    }
+
+    fn push_scope<T>(stack: &mut Vec<T>, pop_stack: &mut Vec<RichTextScope>, scope: RichTextScope, v: T, scoped: bool) {
+        if !scoped {
+            stack.pop();
+        }
+        pop_stack.push(scope);
+        stack.push(v);
+    }
+
+    fn push_zip(&mut self, start: usize) -> Result<(), RichTextError> {
+        if self.zip.is_some() {
+            return Err(RichTextError::ZipInZip);
+        }
+        self.zip = Some((start, self.style(), Vec::new()));
+        self.pop_stack.push(RichTextScope::Zip);
+        Ok(())
+    }
+
+    fn pop_zip(&mut self, end: usize) -> Result<(), RichTextError> {
+        let (start, style, spans) = self.zip.take().ok_or(RichTextError::HierarchyMismatch)?;
+        self.spans.push(RichTextSpan { segment: RichTextSegment::Zip(spans), style, position: start..end });
+        Ok(())
+    }
+}
+
+impl RichTextAst {
+    /// Parse a rich text markup string.
+    pub fn parse(source: &str) -> Result<Self, RichTextParseError> {
+        use xi_unicode::LineBreakIterator;
+
+        let s = source.trim();
+        let pos = |x: &str| {
+            let start = x.as_ptr() as usize - source.as_ptr() as usize;
+            start..start + x.len()
+        };
+
+        let mut last = 0;
+        let mut iter = LineBreakIterator::new(s).map(|(next, _)| {
+            let string = &s[last..next];
+            last = next;
+            string
+        }).flat_map(|s| FindSplit {
+            s,
+            pat: &['{', '}', ':', '@', '*', '+', '#', '_', '~', ' ', '\n', '\t'],
+            one: false,
+        }).peekable();
+
+        let mut parser = RichTextParser {
+            color_stack: Vec::new(),
+            size_stack: Vec::new(),
+            font_stack: Vec::new(),
+            anchor_stack: Vec::new(),
+            link_stack: Vec::new(),
+            pop_stack: Vec::new(),
+            font_style: FontStyle::None,
+            decoration: TextDecoration::None,
+            zip: None,
+            spans: Vec::new(),
+        };
+        let mut at = 0..0;
+
+        macro_rules! next {
+            () => {
+                iter.next().map(|x| {
+                    at = pos(x);
+                    x
+                })
+            };
+        }
+
+        macro_rules! tri {
+            ($e: expr) => {
+                match $e {
+                    Ok(v) => v,
+                    Err(e) => return Err(RichTextParseError { position: at, error: e.into() }),
+                }
+            };
+        }
+
+        // Join tokens in `s` until `stop` or `}`.
+        macro_rules! join_until {
+            ($first: expr, $stop: expr) => {
+                {
+                    let first: &str = $first;
+                    let start = first.as_ptr() as usize - s.as_ptr() as usize;
+                    let mut end = start + first.len();
+                    while let Some(next) = iter.next_if(|x| *x != $stop && *x != "}") {
+                        end += next.len();
+                    }
+                    at.end = pos(&s[end..end]).end;
+                    &s[start..end]
+                }
+            };
+        }
+
+        while let Some(item) = next!() {
+            let start = at.start;
+            match item {
+                "{" => {
+                    let mut cc = tri!(next!().ok_or(RichTextError::BracketsNotClosed));
+                    let prefix = match cc {
+                        "{" => {
+                            parser.push(RichTextSegment::Text("{".to_owned()), start..at.end);
+                            continue;
+                        }
+                        "}" => {
+                            if next!() != Some("}") {
+                                tri!(Err(RichTextError::BracketsNotClosed));
+                            }
+                            parser.push(RichTextSegment::Text("}".to_owned()), start..at.end);
+                            continue;
+                        }
+                        "*" => Some('*'),
+                        "+" => Some('+'),
+                        "_" => Some('_'),
+                        "~" => Some('~'),
+                        "@" => Some('@'),
+                        "#" => Some('#'),
+                        _ => None,
+                    };
+                    if let Some(prefix) = prefix {
+                        cc = tri!(next!().ok_or(RichTextError::BracketsNotClosed));
+                        if cc == "}" {
+                            parser.push(RichTextSegment::Text(prefix.to_string()), start..at.end);
+                            continue;
+                        }
+                    }
+                    // link ids and font names may contain reserved characters.
+                    if prefix.is_none() && cc.get(..5).is_some_and(|x| x.eq_ignore_ascii_case("link=")) {
+                        cc = join_until!(cc, ":");
+                    } else if prefix == Some('@') && cc != ":" {
+                        cc = join_until!(cc, ":");
+                    }
+                    let scoped = if cc.ends_with(':') {
+                        let len = cc.len();
+                        cc = &cc[..len - 1];
+                        true
+                    } else {
+                        match next!() {
+                            Some(":") => true,
+                            Some("}") => false,
+                            Some(cc) => tri!(Err(RichTextError::NotColonOrEndParam(cc.to_owned()))),
+                            None => tri!(Err(RichTextError::NotColonOrEndParam("end of string.".to_owned()))),
+                        }
+                    };
+                    let p = &mut parser;
+                    match cc.to_lowercase().as_str() {
+                        "br" => p.push(RichTextSegment::LineBreak, start..at.end),
+                        "zip" => tri!(p.push_zip(start)),
+                        "img" if scoped => {
+                            let name = tri!(next!().ok_or(RichTextError::BracketsNotClosed));
+                            // image names may contain reserved characters.
+                            let name = join_until!(name, "}");
+                            if next!() != Some("}") {
+                                tri!(Err(RichTextError::BracketsNotClosed));
+                            }
+                            p.push(RichTextSegment::Image(name.trim().to_owned()), start..at.end);
+                        },
+                        _ if prefix.is_none() && cc.get(..5).is_some_and(|x| x.eq_ignore_ascii_case("link=")) => {
+                            // ids are case sensitive.
+                            RichTextParser::push_scope(&mut p.link_stack, &mut p.pop_stack, RichTextScope::Link, cc[5..].to_owned(), scoped);
+                        },
+                        cc => if let (None, Some((_, anchor))) = (prefix, ANCHORS.iter().find(|(name, _)| *name == cc)) {
+                            RichTextParser::push_scope(&mut p.anchor_stack, &mut p.pop_stack, RichTextScope::Anchor, *anchor, scoped);
+                        } else {
+                            match prefix {
+                                Some('@') => RichTextParser::push_scope(&mut p.font_stack, &mut p.pop_stack, RichTextScope::Font, cc.to_owned(), scoped),
+                                Some('+') => {
+                                    let size = tri!(cc.parse::<f32>());
+                                    RichTextParser::push_scope(&mut p.size_stack, &mut p.pop_stack, RichTextScope::Size, FontSize::Pixels(size), scoped);
+                                },
+                                Some('*') => {
+                                    let size = tri!(cc.parse::<f32>());
+                                    RichTextParser::push_scope(&mut p.size_stack, &mut p.pop_stack, RichTextScope::Size, FontSize::Ems(size), scoped);
+                                },
+                                Some('#') => {
+                                    let b = cc.as_bytes();
+                                    let color = match b {
+                                        [a,b,c] => Color::rgba_linear(tri!(hex1(*a)), tri!(hex1(*b)), tri!(hex1(*c)), 1.0),
+                                        [a,b,c,d] => Color::rgba_linear(tri!(hex1(*a)), tri!(hex1(*b)), tri!(hex1(*c)), tri!(hex1(*d))),
+                                        [a,b,c,d,e,f] => Color::rgba_linear(tri!(hex2(*a, *b)), tri!(hex2(*c, *d)), tri!(hex2(*e, *f)), 1.0),
+                                        [a,b,c,d,e,f,g,h] => Color::rgba_linear(tri!(hex2(*a, *b)), tri!(hex2(*c, *d)), tri!(hex2(*e, *f)), tri!(hex2(*g, *h))),
+                                        _ => tri!(Err(RichTextError::InvalidHexColor(cc.to_owned())))
+                                    };
+                                    RichTextParser::push_scope(&mut p.color_stack, &mut p.pop_stack, RichTextScope::Color, color, scoped);
+                                },
+                                Some(pfx) => tri!(Err(RichTextError::UnsupportedPrefix(pfx))),
+                                None => {
+                                    if let Some([r, g, b, a]) = parse_color::parse_flat_lower(cc) {
+                                        let color = Color::rgba_linear(
+                                            r as f32 / 255.0,
+                                            g as f32 / 255.0,
+                                            b as f32 / 255.0,
+                                            a as f32 / 255.0,
+                                        );
+                                        RichTextParser::push_scope(&mut p.color_stack, &mut p.pop_stack, RichTextScope::Color, color, scoped);
+                                    } else {
+                                        tri!(Err(RichTextError::InvalidControlCode(cc.to_owned())))
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                "*" => {
+                    let mut flag = FontStyle::Italic;
+                    if iter.next_if_eq(&"*").is_some() {
+                        flag = FontStyle::Bold;
+                        if iter.next_if_eq(&"*").is_some() {
+                            flag = FontStyle::Bold | FontStyle::Italic;
+                        }
+                    }
+                    parser.font_style ^= flag;
+                }
+                "_" | "~" => {
+                    if iter.next_if_eq(&item).is_some() {
+                        parser.decoration ^= if item == "_" {
+                            TextDecoration::Underline
+                        } else {
+                            TextDecoration::Strikethrough
+                        };
+                    } else {
+                        parser.push(RichTextSegment::Text(item.to_owned()), at.clone());
+                    }
+                }
+                "}" => {
+                    // `}}` closes two scopes if in scope.
+                    if parser.pop_stack.is_empty() && iter.next_if_eq(&"}").is_some() {
+                        parser.push(RichTextSegment::Text("}".to_owned()), start..at.end + 1);
+                        continue;
+                    }
+                    match parser.pop_stack.pop() {
+                        Some(RichTextScope::Anchor) => { parser.anchor_stack.pop(); },
+                        Some(RichTextScope::Color) => { parser.color_stack.pop(); },
+                        Some(RichTextScope::Font) => { parser.font_stack.pop(); },
+                        Some(RichTextScope::Size) => { parser.size_stack.pop(); },
+                        Some(RichTextScope::Link) => { parser.link_stack.pop(); },
+                        Some(RichTextScope::Zip) => tri!(parser.pop_zip(at.end)),
+                        None => tri!(Err(RichTextError::BracketsMismatch)),
+                    }
+                },
+                s if is_ws(s) => {
+                    let mut lines = newlines(s);
+                    while let Some(ws) = iter.next_if(|x| is_ws(x)) {
+                        lines += newlines(ws);
+                        at.end = pos(ws).end;
+                    }
+                    if iter.peek().is_some() {
+                        match lines {
+                            0|1 => parser.push_space(start..at.end),
+                            x => for _ in 0..x-1 {
+                                parser.push(RichTextSegment::LineBreak, start..at.end)
+                            }
+                        }
+                    }
+                },
+                s => parser.push(RichTextSegment::Text(s.to_owned()), at.clone()),
+            }
+        }
+        if parser.zip.is_some() {
+            tri!(parser.pop_zip(source.len()));
+        }
+        // Trailing whitespaces are trimmed, including those before markers like `*`.
+        if let Some(RichTextSpan { segment: RichTextSegment::Space, .. }) = parser.spans.last() {
+            parser.spans.pop();
+        }
+        Ok(RichTextAst { spans: parser.spans })
+    }
+}
+
+/// Write text with reserved characters escaped.
+fn escape(f: &mut Formatter<'_>, text: &str) -> std::fmt::Result {
+    for c in text.chars() {
+        match c {
+            '{' => f.write_str("{{")?,
+            '}' => f.write_str("{}}")?,
+            '*' | '_' | '~' => write!(f, "{{{c}}}")?,
+            c => f.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Write markers that toggle `font_style` and `decoration`.
+fn write_toggles(f: &mut Formatter<'_>, font_style: FontStyle, decoration: TextDecoration) -> std::fmt::Result {
+    match (font_style.contains(FontStyle::Bold), font_style.contains(FontStyle::Italic)) {
+        (true, true) => f.write_str("***")?,
+        (true, false) => f.write_str("**")?,
+        (false, true) => f.write_str("*")?,
+        (false, false) => (),
+    }
+    if decoration.contains(TextDecoration::Underline) {
+        f.write_str("__")?;
+    }
+    if decoration.contains(TextDecoration::Strikethrough) {
+        f.write_str("~~")?;
+    }
+    Ok(())
+}
+
+/// Write spans as markup, `font_style` and `decoration` are toggled
+/// from their values in `state` to their values in `after`.
+fn write_spans(
+    f: &mut Formatter<'_>,
+    spans: &[RichTextSpan],
+    state: &mut (FontStyle, TextDecoration),
+    after: (FontStyle, TextDecoration),
+) -> std::fmt::Result {
+    let toggles = |x: &RichTextSpan| (x.style.font_style, x.style.decoration);
+    // Spans that only differ in `font_style` and `decoration` share scopes.
+    let scoped = |x: &RichTextStyle| (x.font.clone(), x.size, x.color, x.anchor, x.link.clone());
+    let mut index = 0;
+    while index < spans.len() {
+        let group = scoped(&spans[index].style);
+        let len = spans[index..].iter().take_while(|x| scoped(&x.style) == group).count();
+        let (font, size, color, anchor, link) = group;
+        let mut scopes = 0;
+        if let Some(font) = font {
+            write!(f, "{{@{font}:")?;
+            scopes += 1;
+        }
+        match size {
+            Some(FontSize::Pixels(v)) => {
+                write!(f, "{{+{v}:")?;
+                scopes += 1;
+            },
+            Some(FontSize::Ems(v)) => {
+                write!(f, "{{*{v}:")?;
+                scopes += 1;
+            },
+            // Not representable in markup.
+            Some(FontSize::Rems(_)) => return Err(std::fmt::Error),
+            Some(FontSize::None) | None => (),
+        }
+        if let Some(color) = color {
+            let [r, g, b, a] = color.as_linear_rgba_f32().map(|x| (x.clamp(0.0, 1.0) * 255.0).round() as u8);
+            write!(f, "{{#{r:02x}{g:02x}{b:02x}{a:02x}:")?;
+            scopes += 1;
+        }
+        if let Some(anchor) = anchor {
+            if let Some((name, _)) = ANCHORS.iter().find(|(_, x)| *x == anchor) {
+                write!(f, "{{{name}:")?;
+                scopes += 1;
+            }
+        }
+        if let Some(link) = link {
+            write!(f, "{{link={link}:")?;
+            scopes += 1;
+        }
+        for (i, span) in spans.iter().enumerate().skip(index).take(len) {
+            write_toggles(f, span.style.font_style ^ state.0, span.style.decoration ^ state.1)?;
+            *state = toggles(span);
+            let next = spans.get(i + 1).map(toggles).unwrap_or(after);
+            match &span.segment {
+                RichTextSegment::Text(text) => escape(f, text)?,
+                RichTextSegment::Space => f.write_char(' ')?,
+                RichTextSegment::LineBreak => f.write_str("{br}")?,
+                RichTextSegment::Image(name) => write!(f, "{{img:{name}}}")?,
+                RichTextSegment::Zip(spans) => {
+                    f.write_str("{zip:")?;
+                    write_spans(f, spans, state, next)?;
+                    f.write_char('}')?;
+                },
+            }
+        }
+        // Toggle inside scopes so scopes contain their markers.
+        if scopes > 0 || index + len == spans.len() {
+            let next = spans.get(index + len).map(toggles).unwrap_or(after);
+            write_toggles(f, next.0 ^ state.0, next.1 ^ state.1)?;
+            *state = next;
+        }
+        for _ in 0..scopes {
+            f.write_char('}')?;
+        }
+        index += len;
+    }
+    Ok(())
+}
+
+impl Display for RichTextAst {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let none = (FontStyle::None, TextDecoration::None);
+        write_spans(f, &self.spans, &mut none.clone(), none)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fonts(source: &str) -> Vec<(String, Option<String>)> {
+        RichTextAst::parse(source).unwrap().spans.into_iter().map(|span| match span.segment {
+            RichTextSegment::Text(text) => (text, span.style.font),
+            segment => (format!("{segment:?}"), span.style.font),
+        }).collect()
+    }
+
+    #[test]
+    fn font_names_with_reserved_characters() {
+        assert_eq!(fonts("{@my_font:hi}"), [("hi".to_owned(), Some("my_font".to_owned()))]);
+        assert_eq!(fonts("{@a~b*c}hi"), [("hi".to_owned(), Some("a~b*c".to_owned()))]);
+        assert_eq!(fonts("{@my_font:__hi__}")[0].1.as_deref(), Some("my_font"));
+    }
+
+    #[test]
+    fn unicode_control_codes() {
+        // `K` (Kelvin sign) lowercases to `k` but is 3 bytes long.
+        assert!(RichTextAst::parse("{lin\u{212A}=x:y}").is_err());
+        assert!(RichTextAst::parse("{\u{212A}\u{212A}}").is_err());
+        assert!(RichTextAst::parse("{link\u{212A}:y}").is_err());
+    }
+
+    fn without_positions(spans: &mut [RichTextSpan]) {
+        for span in spans {
+            span.position = 0..0;
+            if let RichTextSegment::Zip(spans) = &mut span.segment {
+                without_positions(spans);
+            }
+        }
+    }
+
+    fn round_trip(source: &str) {
+        let mut ast = RichTextAst::parse(source).unwrap();
+        let markup = ast.to_string();
+        let mut parsed = RichTextAst::parse(&markup)
+            .unwrap_or_else(|e| panic!("{source:?} displays as {markup:?}: {e}"));
+        without_positions(&mut ast.spans);
+        without_positions(&mut parsed.spans);
+        assert_eq!(ast, parsed, "{source:?} displays as {markup:?}");
+    }
+
+    #[test]
+    fn round_trip_scopes() {
+        round_trip("Hello, {red:world!}");
+        round_trip("{@font:{+24:{#abc:{left:a {*1.5:b} c}}} d}");
+        round_trip("{red}a{blue}b{green:c}");
+        round_trip("{@a:x}{@b:y}{@a:z}");
+        round_trip("{center:{right:{top:x}} y}");
+    }
+
+    #[test]
+    fn round_trip_toggles() {
+        round_trip("*a* **b** ***c*** __d__ ~~e~~");
+        round_trip("**a {red:b** c} d");
+        round_trip("__a {+12:b ~~c}__ d~~ e");
+        round_trip("***a {red:**b} c*");
+        round_trip("*unclosed");
+    }
+
+    #[test]
+    fn round_trip_zip() {
+        round_trip("a {zip:b c} d");
+        round_trip("{red:a {zip:**b c**}} d");
+        round_trip("a {zip:b *c} d* e");
+        round_trip("{zip:unclosed");
+    }
+
+    #[test]
+    fn round_trip_links() {
+        round_trip("{link=home:Home} {LINK=a_b~c*:x}");
+        round_trip("{link=outer:a {link=inner:b} c}");
+        round_trip("{link=a}x{img:icon}");
+    }
+
+    #[test]
+    fn round_trip_escapes() {
+        round_trip("{{ {}} {_} {~} {*} a{{b}}c");
+        round_trip("snake_case ~tilde *");
+        round_trip("a{br}b{br}{br}c");
+        round_trip("a\n\n\nb");
+        round_trip("{img: my_icon~}");
+    }
+
+    #[test]
+    fn display_merges_adjacent_text() {
+        let ast = RichTextAst::parse("zip\u{e9}~~~~b").unwrap();
+        let parsed = RichTextAst::parse(&ast.to_string()).unwrap();
+        assert_eq!(ast.spans.len(), 2);
+        assert_eq!(parsed.spans.len(), 1);
+        assert_eq!(parsed.to_string(), ast.to_string());
+    }
+
+    #[test]
+    fn invalid_hex_digits() {
+        let error = |source: &str| RichTextAst::parse(source).unwrap_err().error;
+        assert!(matches!(error("{#gg0000}"), RichTextError::InvalidHexDigit(b'g')));
+        assert!(matches!(error("{#12imgb:x}"), RichTextError::InvalidHexDigit(b'i')));
+        assert!(matches!(error("{#fg0}"), RichTextError::InvalidHexDigit(b'g')));
+        assert!(RichTextAst::parse("{#abcdef:x}").is_ok());
+    }
+
+    #[test]
+    fn rems_cannot_be_displayed() {
+        let mut ast = RichTextAst::parse("{+12:a}").unwrap();
+        ast.spans[0].style.size = Some(FontSize::Rems(1.0));
+        let mut string = String::new();
+        assert!(write!(string, "{ast}").is_err());
+    }
+}
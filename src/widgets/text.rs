@@ -1,20 +1,27 @@
+use std::borrow::Cow;
+
 use bevy::{asset::{Assets, Handle}, reflect::Reflect, render::render_asset::RenderAssetUsages};
+use bevy::math::Vec2;
 use bevy::render::texture::Image;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
-use bevy::text::{Font, Text, TextStyle};
-use bevy::ecs::{component::Component, query::Changed, world::Mut};
+use bevy::sprite::Sprite;
+use bevy::text::{BreakLineOn, Font, JustifyText, Text, TextStyle};
+use bevy::ecs::{component::Component, query::{Changed, With}, world::Mut};
 use bevy::ecs::system::{Query, Res, ResMut};
 
-use crate::{DimensionData, util::ScalingFactor, Coloring};
+use crate::{Dimension, DimensionData, DimensionMut, util::ScalingFactor, Coloring};
 
 use ab_glyph::{Font as _, point};
 use ab_glyph::ScaleFont as _;
 
 /// A simple text manager representing a fragment of text,
-/// fragment as in no explicit support for linebreaks.
-/// When paired with [`Sprite`](bevy::sprite::Sprite), this will render it with `ab_glyph`,
+/// fragment as in a single line unless `wrap` is set.
+/// When paired with [`Sprite`], this will render it with `ab_glyph`,
 /// when paired when [`Text`] this will update its contents.
 ///
+/// If the [`Dimension`] is owned, the text is rendered inside its bounds,
+/// if copied, the measured size of the text is copied to [`DimensionData`].
+///
 /// This struct is designed with change detection in mind to maximize performance.
 #[derive(Debug, Clone, Default, PartialEq, Component, Reflect)]
 #[non_exhaustive]
@@ -22,6 +29,19 @@ pub struct TextFragment {
     pub text: String,
     pub font: Handle<Font>,
     pub size: f32,
+    /// If set, break lines with `xi-unicode` to fit the width of `bounds`
+    /// and respect newlines in `text`.
+    pub wrap: bool,
+    /// Horizontal alignment of lines inside `bounds`.
+    pub alignment: JustifyText,
+    /// If set, truncate lines and characters outside of `bounds` with an ellipsis.
+    ///
+    /// Only supported when paired with [`Sprite`].
+    pub ellipsis: bool,
+    /// Bounds of the text, synchronized from an owned [`Dimension`].
+    ///
+    /// Axis with a non-positive value is unbounded.
+    pub bounds: Vec2,
 }
 
 impl TextFragment {
@@ -38,6 +58,23 @@ impl TextFragment {
         self
     }
 
+    /// Break lines to fit the width of an owned [`Dimension`].
+    pub fn with_wrap(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: JustifyText) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Truncate text that does not fit in an owned [`Dimension`] with an ellipsis.
+    pub fn with_ellipsis(mut self) -> Self {
+        self.ellipsis = true;
+        self
+    }
+
     /// Does not change if value is not changed
     pub fn set_text(s: &mut Mut<Self>, value: &str) {
         if s.text != value {
@@ -58,6 +95,13 @@ impl TextFragment {
             s.size = em
         }
     }
+
+    /// Does not change if value is not changed
+    pub fn set_bounds(s: &mut Mut<Self>, bounds: Vec2) {
+        if s.bounds != bounds {
+            s.bounds = bounds
+        }
+    }
 }
 
 pub fn sync_em_text_fragment(
@@ -68,12 +112,43 @@ pub fn sync_em_text_fragment(
     })
 }
 
+/// Copy the measured size of a [`TextFragment`] to a copied [`Dimension`],
+/// instead of the size of its rendered image.
+pub fn copy_dimension_text_fragment(
+    mut query: Query<(&Sprite, DimensionMut), With<TextFragment>>
+) {
+    query.iter_mut().for_each(|(sprite, mut dimension)| {
+        // `custom_size` is written by `sync_sprite_text_fragment` if copied.
+        if let Some(size) = sprite.custom_size.filter(|_| dimension.is_copied()) {
+            dimension.update_size(|| size)
+        }
+    })
+}
+
+pub fn sync_bounds_text_fragment(
+    mut query: Query<(&Dimension, &DimensionData, &mut TextFragment)>
+) {
+    query.iter_mut().for_each(|(dimension, data, mut frag)| {
+        if dimension.is_copied() {
+            TextFragment::set_bounds(&mut frag, Vec2::ZERO)
+        } else {
+            TextFragment::set_bounds(&mut frag, data.size)
+        }
+    })
+}
+
 
 pub fn sync_text_text_fragment(
     mut query: Query<(&mut Text, &Coloring, &TextFragment), Changed<TextFragment>, >
 ) {
     query.iter_mut().for_each(|(mut text, color, frag)| {
         if frag.size <= 0.0 {return}
+        text.justify = frag.alignment;
+        text.linebreak_behavior = if frag.wrap {
+            BreakLineOn::WordBoundary
+        } else {
+            BreakLineOn::NoWrap
+        };
         text.sections.clear();
         text.sections.push(bevy::text::TextSection {
             value: frag.text.clone(),
@@ -100,46 +175,160 @@ pub fn measure_string<F: ab_glyph::Font>(
     cursor
 }
 
+/// Break `text` into lines no wider than `max_width`, if positive.
+///
+/// Lines are only broken on `xi-unicode` break opportunities
+/// and may exceed `max_width` if no opportunity is available.
+pub fn wrap_string<'t, F: ab_glyph::Font>(
+    font: &impl ab_glyph::ScaleFont<F>,
+    text: &'t str,
+    max_width: f32,
+) -> Vec<&'t str> {
+    use xi_unicode::LineBreakIterator;
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        if max_width <= 0.0 {
+            lines.push(paragraph.trim_end());
+            continue;
+        }
+        let mut start = 0;
+        let mut end = 0;
+        for (next, _) in LineBreakIterator::new(paragraph) {
+            let line = paragraph[start..next].trim_end();
+            if end > start && measure_string(font, line) > max_width {
+                lines.push(paragraph[start..end].trim_end());
+                start = end;
+            }
+            end = next;
+        }
+        lines.push(paragraph[start..].trim_end());
+    }
+    if lines.is_empty() {
+        lines.push("");
+    }
+    lines
+}
+
+/// Truncate `line` with an ellipsis so that it fits `max_width`.
+///
+/// The whole line is kept if it fits with the ellipsis,
+/// i.e. when truncated only by the number of lines.
+fn truncate_string<F: ab_glyph::Font>(
+    font: &impl ab_glyph::ScaleFont<F>,
+    line: &str,
+    max_width: f32,
+) -> String {
+    let ellipsis = if font.glyph_id('…').0 == 0 { "..." } else { "…" };
+    let mut result = String::new();
+    let ends = std::iter::once(line.len()).chain(line.char_indices().rev().map(|(index, _)| index));
+    for index in ends {
+        let prefix = line[..index].trim_end();
+        if measure_string(font, prefix) + measure_string(font, ellipsis) <= max_width {
+            result.push_str(prefix);
+            break;
+        }
+    }
+    result.push_str(ellipsis);
+    result
+}
+
+/// Horizontal start of a line of `line_width` in `area_width`.
+fn align(alignment: JustifyText, area_width: f32, line_width: f32) -> f32 {
+    match alignment {
+        JustifyText::Left => 0.0,
+        JustifyText::Center => ((area_width - line_width) / 2.0).floor(),
+        JustifyText::Right => area_width - line_width,
+    }
+}
+
 pub fn sync_sprite_text_fragment(
     scale_factor: ScalingFactor,
     mut images: ResMut<Assets<Image>>,
     fonts: Res<Assets<Font>>,
-    mut query: Query<(&TextFragment, &Handle<Image>), Changed<TextFragment>>
+    mut query: Query<(&TextFragment, &Handle<Image>, Option<&mut Sprite>), Changed<TextFragment>>
 ) {
     let scale_factor = scale_factor.get();
-    for (fragment, handle) in query.iter_mut() {
+    for (fragment, handle, sprite) in query.iter_mut() {
         if fragment.size <= 0.0 {continue;}
         let font = match fonts.get(&fragment.font) {
             Some(font) => font.font.as_scaled(fragment.size * scale_factor),
             None => continue,
         };
         let Some(image) = images.get_mut(handle) else {continue};
-        let dimension = measure_string(&font, &fragment.text);
-        let height = font.height().ceil();
-        let width = (dimension.ceil() as usize).max(1);
-        let height = (height.ceil() as usize).max(1);
+        let bounds = fragment.bounds * scale_factor;
+        let line_height = font.height() + font.line_gap();
+
+        let mut lines: Vec<Cow<str>> = if fragment.wrap {
+            wrap_string(&font, &fragment.text, bounds.x)
+                .into_iter()
+                .map(Cow::Borrowed)
+                .collect()
+        } else {
+            vec![Cow::Borrowed(fragment.text.as_str())]
+        };
+        let mut truncated = false;
+        if bounds.y > 0.0 {
+            let max_lines = (((bounds.y + font.line_gap()) / line_height) as usize).max(1);
+            truncated = lines.len() > max_lines;
+            lines.truncate(max_lines);
+        }
+        if fragment.ellipsis && bounds.x > 0.0 {
+            let count = lines.len();
+            for (index, line) in lines.iter_mut().enumerate() {
+                if (truncated && index == count - 1) || measure_string(&font, line) > bounds.x {
+                    *line = Cow::Owned(truncate_string(&font, line, bounds.x));
+                }
+            }
+        }
+
+        let widths: Vec<f32> = lines.iter().map(|line| measure_string(&font, line)).collect();
+        let dimension = Vec2::new(
+            widths.iter().copied().fold(0.0, f32::max),
+            lines.len() as f32 * line_height - font.line_gap(),
+        );
+        let area = Vec2::new(
+            if bounds.x > 0.0 { bounds.x } else { dimension.x },
+            if bounds.y > 0.0 { bounds.y } else { dimension.y },
+        );
+        let width = (area.x.ceil() as usize).max(1);
+        let height = (area.y.ceil() as usize).max(1);
         let mut buffer = vec![0u8; width * height * 4];
 
-        let mut cursor = 0.0;
-        let mut last = '\0';
-        for c in fragment.text.chars() {
-            let mut glyph = font.scaled_glyph(c);
-            glyph.position = point(cursor, 0.0 + font.ascent());
-            cursor += font.kern(font.glyph_id(last), font.glyph_id(c));
-            cursor += font.h_advance(font.glyph_id(c));
-            last = c;
-            if let Some(glyph) = font.outline_glyph(glyph) {
-                let bounds = glyph.px_bounds();
-                glyph.draw(|x, y, v| {
-                    let x = x as usize + bounds.min.x as usize;
-                    let y = y as usize + bounds.min.y as usize;
-                    if x < width && y < height {
-                        buffer[(x + y * width) * 4] = 255;
-                        buffer[(x + y * width) * 4 + 1] = 255;
-                        buffer[(x + y * width) * 4 + 2] =  255;
-                        buffer[(x + y * width) * 4 + 3] += (v * 255.0) as u8;
-                    }
-                })
+        for (index, (line, line_width)) in lines.iter().zip(widths).enumerate() {
+            let mut cursor = align(fragment.alignment, area.x, line_width);
+            let baseline = index as f32 * line_height + font.ascent();
+            let mut last = '\0';
+            for c in line.chars() {
+                cursor += font.kern(font.glyph_id(last), font.glyph_id(c));
+                let mut glyph = font.scaled_glyph(c);
+                glyph.position = point(cursor, baseline);
+                cursor += font.h_advance(font.glyph_id(c));
+                last = c;
+                if let Some(glyph) = font.outline_glyph(glyph) {
+                    let bounds = glyph.px_bounds();
+                    glyph.draw(|x, y, v| {
+                        let x = x as i32 + bounds.min.x as i32;
+                        let y = y as i32 + bounds.min.y as i32;
+                        if x < 0 || y < 0 {
+                            return;
+                        }
+                        let (x, y) = (x as usize, y as usize);
+                        if x < width && y < height {
+                            buffer[(x + y * width) * 4] = 255;
+                            buffer[(x + y * width) * 4 + 1] = 255;
+                            buffer[(x + y * width) * 4 + 2] =  255;
+                            buffer[(x + y * width) * 4 + 3] = buffer[(x + y * width) * 4 + 3]
+                                .saturating_add((v * 255.0) as u8);
+                        }
+                    })
+                }
+            }
+        }
+
+        // Sprites of copied dimensions are sized by the measured text.
+        if let Some(mut sprite) = sprite {
+            if fragment.bounds.cmple(Vec2::ZERO).any() {
+                sprite.custom_size = Some(area / scale_factor);
             }
         }
 
@@ -150,3 +339,61 @@ pub fn sync_sprite_text_fragment(
         }, TextureDimension::D2, buffer, TextureFormat::Rgba8Unorm, RenderAssetUsages::all())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ab_glyph::{FontRef, PxScaleFont};
+    use super::*;
+
+    fn font() -> PxScaleFont<FontRef<'static>> {
+        FontRef::try_from_slice(include_bytes!("../../assets/RobotoCondensed.ttf"))
+            .unwrap()
+            .into_scaled(16.0)
+    }
+
+    #[test]
+    fn wrap() {
+        let font = font();
+        let width = measure_string(&font, "Hello world");
+        assert_eq!(wrap_string(&font, "Hello world", 0.0), ["Hello world"]);
+        assert_eq!(wrap_string(&font, "Hello world", width), ["Hello world"]);
+        assert_eq!(wrap_string(&font, "Hello world", width - 1.0), ["Hello", "world"]);
+        assert_eq!(wrap_string(&font, "Hello big world", measure_string(&font, "Hello big")), ["Hello big", "world"]);
+        // Words longer than the width are not broken.
+        assert_eq!(wrap_string(&font, "Hello world", 1.0), ["Hello", "world"]);
+        assert_eq!(wrap_string(&font, "", 100.0), [""]);
+    }
+
+    #[test]
+    fn wrap_newlines() {
+        let font = font();
+        assert_eq!(wrap_string(&font, "a\nb\n\nc", 0.0), ["a", "b", "", "c"]);
+        assert_eq!(wrap_string(&font, "a b\r\nc", 1.0), ["a", "b", "c"]);
+        assert_eq!(wrap_string(&font, "trailing  \n", 100.0), ["trailing"]);
+    }
+
+    #[test]
+    fn ellipsis() {
+        let font = font();
+        let ellipsis = measure_string(&font, "…");
+        let width = measure_string(&font, "Hello world");
+        // Truncated only by the number of lines.
+        assert_eq!(truncate_string(&font, "Hello world", width + ellipsis), "Hello world…");
+        assert_eq!(truncate_string(&font, "Hello world", width + ellipsis - 0.1), "Hello worl…");
+        assert_eq!(truncate_string(&font, "Hello world", measure_string(&font, "Hello worl…")), "Hello worl…");
+        let truncated = truncate_string(&font, "Hello world", width / 2.0);
+        assert!(measure_string(&font, &truncated) <= width / 2.0);
+        assert!(truncated.starts_with("Hel") && truncated.ends_with('…'));
+        // Whitespaces before the ellipsis are trimmed.
+        assert_eq!(truncate_string(&font, "Hello world", measure_string(&font, "Hello …")), "Hello…");
+        assert_eq!(truncate_string(&font, "Hello world", 0.0), "…");
+    }
+
+    #[test]
+    fn alignment() {
+        assert_eq!(align(JustifyText::Left, 100.0, 40.0), 0.0);
+        assert_eq!(align(JustifyText::Center, 100.0, 40.0), 30.0);
+        assert_eq!(align(JustifyText::Center, 100.0, 41.0), 29.0);
+        assert_eq!(align(JustifyText::Right, 100.0, 40.0), 60.0);
+    }
+}
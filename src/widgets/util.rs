@@ -1,8 +1,8 @@
 use std::mem;
 
-use bevy::{ecs::{query::{With, Without}, entity::Entity, system::{Commands, Query, Res, Resource}, component::Component}, hierarchy::Children, window::{PrimaryWindow, Window, CursorIcon}, reflect::Reflect};
+use bevy::{ecs::{query::{With, Without}, entity::Entity, system::{Commands, Local, Query, Res, Resource}, component::Component}, hierarchy::Children, window::{PrimaryWindow, Window, CursorIcon}, reflect::Reflect};
 
-use crate::{anim::VisibilityToggle, dsl::prelude::EventFlags, events::CursorFocus};
+use crate::{anim::VisibilityToggle, dsl::prelude::EventFlags, events::{CursorFocus, CursorState}};
 
 use super::button::CheckButtonState;
 
@@ -67,21 +67,28 @@ impl Default for CursorDefault {
 
 pub(crate) fn set_cursor(
     default_cursor: Option<Res<CursorDefault>>,
-    mut window: Query<&mut Window, With<PrimaryWindow>>,
+    state: Res<CursorState>,
+    primary: Query<Entity, With<PrimaryWindow>>,
+    mut windows: Query<&mut Window>,
     query: Query<(&SetCursor, &CursorFocus)>,
+    mut previous: Local<Option<Entity>>,
 ){
+    let Some(entity) = state.window().or_else(|| primary.get_single().ok()) else {return};
+    // Reset the icon of the window the cursor left.
+    if let Some(left) = previous.replace(entity).filter(|x| *x != entity) {
+        if let Ok(mut window) = windows.get_mut(left) {
+            window.cursor.icon = default_cursor.as_ref().map(|x| x.0).unwrap_or_default();
+        }
+    }
+    let Ok(mut window) = windows.get_mut(entity) else {return};
     for (cursor, focus) in query.iter() {
         if cursor.flags.contains(focus.flags()) {
-            if let Ok(mut window) = window.get_single_mut() {
-                window.cursor.icon = cursor.icon;
-            }
+            window.cursor.icon = cursor.icon;
             return;
         }
     }
     if let Some(icon) = default_cursor{
-        if let Ok(mut window) = window.get_single_mut() {
-            window.cursor.icon = icon.0;
-        }
+        window.cursor.icon = icon.0;
     }
 }
 
@@ -0,0 +1,389 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use bevy::ecs::entity::Entity;
+use bevy::ecs::component::Component;
+use bevy::ecs::query::Without;
+use bevy::ecs::system::Query;
+use bevy::ecs::world::World;
+use bevy::hierarchy::{BuildChildren, Parent};
+use crate::util::{RCommands, Rem, WidgetBuilder, WindowSize};
+use crate::{Anchor, Dimension, DimensionData, DimensionType, Opacity, Size, Size2, SizeUnit, Transform2D};
+
+/// Height of rows in a [`VirtualList`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowHeight {
+    /// Every row has the same height.
+    Fixed(Size),
+    /// Rows are measured from their [`DimensionData`] after being laid out,
+    /// the value is used as an estimate for rows not yet measured.
+    Measured(Size),
+}
+
+impl Default for RowHeight {
+    fn default() -> Self {
+        RowHeight::Fixed(Size::new(SizeUnit::Em, 1.0))
+    }
+}
+
+/// Updates a recycled row of a [`VirtualList`] to display a new index.
+#[derive(Clone)]
+pub struct RowRebind(Arc<dyn Fn(&mut World, Entity, usize) + Send + Sync + 'static>);
+
+impl std::fmt::Debug for RowRebind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RowRebind").finish()
+    }
+}
+
+impl RowRebind {
+    pub fn new(f: impl Fn(&mut World, Entity, usize) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+/// Marker for a row of a [`VirtualList`] in its pool,
+/// storing the row's [`Opacity`] to restore on reuse.
+#[derive(Debug, Clone, Copy, Component)]
+#[component(storage="SparseSet")]
+pub struct PooledRow {
+    opacity: f32,
+    disabled: bool,
+}
+
+/// A list that only spawns rows visible in its parent's viewport.
+///
+/// Rows are built from a [`WidgetBuilder<usize>`] with the row's index
+/// and positioned top to bottom.
+/// Rows that stay in view are kept between frames.
+///
+/// If a [`RowRebind`] is set, rows that leave the viewport are hidden and kept in a pool,
+/// then rebound to new indices instead of building new rows,
+/// otherwise rows that leave the viewport are despawned.
+/// Pooled rows are hidden by setting their [`Opacity`] to `0` and disabling them.
+///
+/// # Setup Requirements
+///
+/// * Add this alongside [`Scrolling::Y`](super::scroll::Scrolling::Y) on a child of the viewport.
+/// * This entity should have `Anchor::TOP_CENTER`, its height is set to the total height of the rows.
+///
+/// Since the height of the list is owned, [`Constraint`](super::constraints::Constraint),
+/// [`SharedPosition`](super::constraints::SharedPosition) and
+/// [`PositionFac`](super::constraints::PositionFac) work the same way as a regular scrolling sprite.
+#[derive(Debug, Clone, Component)]
+pub struct VirtualList {
+    count: usize,
+    builder: WidgetBuilder<usize>,
+    height: RowHeight,
+    /// Number of extra rows spawned before and after the viewport.
+    pub overscan: usize,
+    rebind: Option<RowRebind>,
+    rows: BTreeMap<usize, Entity>,
+    pool: Vec<Entity>,
+    measured: Vec<Option<f32>>,
+    tops: Vec<f32>,
+    rebuild: bool,
+}
+
+impl VirtualList {
+    pub fn new(count: usize, builder: WidgetBuilder<usize>, height: RowHeight) -> Self {
+        Self {
+            count,
+            builder,
+            height,
+            overscan: 1,
+            rebind: None,
+            rows: BTreeMap::new(),
+            pool: Vec::new(),
+            measured: Vec::new(),
+            tops: Vec::new(),
+            rebuild: false,
+        }
+    }
+
+    /// Set the number of extra rows spawned before and after the viewport.
+    pub fn with_overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    /// Recycle rows that leave the viewport, rebinding them to new indices.
+    pub fn with_rebind(mut self, rebind: impl Fn(&mut World, Entity, usize) + Send + Sync + 'static) -> Self {
+        self.rebind = Some(RowRebind::new(rebind));
+        self
+    }
+
+    /// Number of items in the list.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Set the number of items in the list.
+    ///
+    /// Rows with index out of bounds are despawned or pooled.
+    pub fn set_count(&mut self, count: usize) {
+        self.count = count;
+        self.measured.truncate(count);
+        self.tops.clear();
+    }
+
+    /// Rebuild all visible rows, or rebind them if recycling.
+    pub fn refresh(&mut self) {
+        self.rebuild = true;
+        self.measured.clear();
+        self.tops.clear();
+    }
+
+    /// Obtain the entity of a row if spawned.
+    pub fn row(&self, index: usize) -> Option<Entity> {
+        self.rows.get(&index).copied()
+    }
+
+    /// Iterate through spawned rows by index.
+    pub fn spawned(&self) -> impl Iterator<Item = (usize, Entity)> + '_ {
+        self.rows.iter().map(|(i, e)| (*i, *e))
+    }
+
+    /// Top of a row, `count` gives the total height.
+    fn top(&self, index: usize, estimate: f32) -> f32 {
+        match self.height {
+            RowHeight::Fixed(_) => index as f32 * estimate,
+            RowHeight::Measured(_) => self.tops[index],
+        }
+    }
+
+    /// Index of the row containing this position, clamped to the last row.
+    fn index_at(&self, position: f32, estimate: f32) -> usize {
+        let index = match self.height {
+            RowHeight::Fixed(_) if estimate > 0.0 => (position / estimate).floor().max(0.0) as usize,
+            RowHeight::Fixed(_) => 0,
+            RowHeight::Measured(_) => self.tops.partition_point(|x| *x <= position).saturating_sub(1),
+        };
+        index.min(self.count.saturating_sub(1))
+    }
+
+    /// Range of rows intersecting `scroll..scroll + viewport`, including overscan.
+    fn visible_range(&self, scroll: f32, viewport: f32, estimate: f32) -> Range<usize> {
+        if self.count == 0 {
+            return 0..0;
+        }
+        let first = self.index_at(scroll, estimate);
+        let last = self.index_at(scroll + viewport, estimate);
+        first.saturating_sub(self.overscan)..(last + self.overscan + 1).min(self.count)
+    }
+
+    /// Hide a row and add it to the pool if recycling, despawn it otherwise.
+    fn release(&mut self, commands: &mut RCommands, row: Entity) {
+        if self.rebind.is_none() {
+            commands.despawn(row);
+            return;
+        }
+        self.pool.push(row);
+        commands.add_command(move |world: &mut World| {
+            let Some(mut entity) = world.get_entity_mut(row) else {return};
+            let pooled = match entity.get_mut::<Opacity>() {
+                Some(mut opacity) => {
+                    let pooled = PooledRow { opacity: opacity.opacity, disabled: opacity.disabled };
+                    opacity.opacity = 0.0;
+                    opacity.disabled = true;
+                    pooled
+                },
+                None => PooledRow { opacity: 1.0, disabled: false },
+            };
+            entity.insert(pooled);
+        });
+    }
+
+    fn recompute_tops(&mut self, estimate: f32) {
+        if self.tops.len() == self.count + 1 {
+            return;
+        }
+        self.measured.resize(self.count, None);
+        self.tops.clear();
+        let mut acc = 0.0;
+        self.tops.push(acc);
+        for height in &self.measured {
+            acc += height.unwrap_or(estimate);
+            self.tops.push(acc);
+        }
+    }
+}
+
+fn place_row(transform: &mut Transform2D, top: f32) {
+    let x = if transform.anchor.is_inherit() {0.0} else {transform.anchor.x()};
+    transform.anchor = Anchor::custom(x, 0.5);
+    transform.parent_anchor = Anchor::INHERIT;
+    let (unit, _) = transform.offset.units();
+    let offset = Size2::new(
+        Size::new(unit, transform.offset.raw().x),
+        Size::new(SizeUnit::Pixels, -top),
+    );
+    if transform.offset != offset {
+        transform.offset = offset;
+    }
+}
+
+pub(crate) fn virtual_list_system(
+    mut commands: RCommands,
+    window_size: WindowSize,
+    rem: Rem,
+    mut lists: Query<(Entity, Option<&Parent>, &mut VirtualList, &Transform2D, &mut Dimension, &DimensionData)>,
+    parent_query: Query<&DimensionData>,
+    mut rows: Query<(&mut Transform2D, &DimensionData), Without<VirtualList>>,
+) {
+    let window_size = window_size.get();
+    let rem = rem.get();
+    for (entity, parent, mut list, transform, mut dimension, dim) in lists.iter_mut() {
+        let viewport = parent
+            .and_then(|x| parent_query.get(**x).ok())
+            .map(|x| x.size)
+            .unwrap_or(window_size);
+        let (RowHeight::Fixed(height) | RowHeight::Measured(height)) = list.height;
+        let estimate = height.as_pixels(viewport.y, dim.em, rem);
+
+        if list.rebuild {
+            list.rebuild = false;
+            for (_, row) in std::mem::take(&mut list.rows) {
+                list.release(&mut commands, row);
+            }
+        }
+
+        if let RowHeight::Measured(_) = list.height {
+            let list = &mut *list;
+            list.measured.resize(list.count, None);
+            let mut changed = false;
+            for (index, row) in list.rows.iter() {
+                let Ok((_, row_dim)) = rows.get(*row) else {continue};
+                if *index >= list.count || row_dim.size.y <= 0.0 {
+                    continue;
+                }
+                let slot = &mut list.measured[*index];
+                if *slot != Some(row_dim.size.y) {
+                    *slot = Some(row_dim.size.y);
+                    changed = true;
+                }
+            }
+            if changed {
+                list.tops.clear();
+            }
+            list.recompute_tops(estimate);
+        }
+
+        let total = list.top(list.count, estimate);
+        if let DimensionType::Owned(size) = dimension.dimension {
+            if size.units().1 != SizeUnit::Pixels || size.raw().y != total {
+                let (unit, _) = size.units();
+                dimension.dimension = DimensionType::Owned(Size2::new(
+                    Size::new(unit, size.raw().x),
+                    Size::new(SizeUnit::Pixels, total),
+                ));
+            }
+        } else {
+            dimension.dimension = DimensionType::Owned(Size2::new(
+                Size::new(SizeUnit::Percent, 1.0),
+                Size::new(SizeUnit::Pixels, total),
+            ));
+        }
+
+        let scroll = transform.offset.as_pixels(viewport, dim.em, rem).y.max(0.0);
+        let range = list.visible_range(scroll, viewport.y, estimate);
+
+        let list = &mut *list;
+        let released: Vec<_> = list.rows.iter()
+            .filter(|(index, _)| !range.contains(index))
+            .map(|(index, row)| (*index, *row))
+            .collect();
+        for (index, row) in released {
+            list.rows.remove(&index);
+            list.release(&mut commands, row);
+        }
+
+        for index in range {
+            let top = list.top(index, estimate);
+            if let Some(row) = list.rows.get(&index) {
+                if let Ok((mut transform, _)) = rows.get_mut(*row) {
+                    place_row(&mut transform, top);
+                }
+                continue;
+            }
+            // Skip pooled rows despawned elsewhere.
+            while list.pool.last().is_some_and(|row| !rows.contains(*row)) {
+                list.pool.pop();
+            }
+            if let (Some(rebind), Some(row)) = (list.rebind.clone(), list.pool.pop()) {
+                commands.add_command(move |world: &mut World| {
+                    let Some(mut entity) = world.get_entity_mut(row) else {return};
+                    if let Some(pooled) = entity.take::<PooledRow>() {
+                        if let Some(mut opacity) = entity.get_mut::<Opacity>() {
+                            opacity.opacity = pooled.opacity;
+                            opacity.disabled = pooled.disabled;
+                        }
+                    }
+                    if let Some(mut transform) = entity.get_mut::<Transform2D>() {
+                        place_row(&mut transform, top);
+                    }
+                    (rebind.0)(world, row, index);
+                });
+                list.rows.insert(index, row);
+                continue;
+            }
+            let row = commands.spawn_fn(&list.builder, index);
+            commands.entity(entity).add_child(row);
+            commands.add_command(move |world: &mut World| {
+                if let Some(mut transform) = world.get_mut::<Transform2D>(row) {
+                    place_row(&mut transform, top);
+                }
+            });
+            list.rows.insert(index, row);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(count: usize, height: RowHeight) -> VirtualList {
+        let builder = WidgetBuilder::new(|_: &mut RCommands, _: usize| Entity::PLACEHOLDER);
+        VirtualList::new(count, builder, height)
+    }
+
+    #[test]
+    fn fixed_range() {
+        let mut list = list(100, RowHeight::Fixed(Size::new(SizeUnit::Pixels, 20.0)));
+        assert_eq!(list.visible_range(0.0, 100.0, 20.0), 0..7);
+        assert_eq!(list.visible_range(50.0, 100.0, 20.0), 1..9);
+        assert_eq!(list.visible_range(1950.0, 100.0, 20.0), 96..100);
+        // Scrolled past the end after the count shrinks.
+        assert_eq!(list.visible_range(5000.0, 100.0, 20.0), 98..100);
+        list.overscan = 0;
+        assert_eq!(list.visible_range(40.0, 39.0, 20.0), 2..4);
+        assert_eq!(list.visible_range(0.0, 100.0, 0.0), 0..1);
+        assert_eq!(list.top(100, 20.0), 2000.0);
+    }
+
+    #[test]
+    fn empty_range() {
+        let list = list(0, RowHeight::Fixed(Size::new(SizeUnit::Pixels, 20.0)));
+        assert_eq!(list.visible_range(0.0, 100.0, 20.0), 0..0);
+    }
+
+    #[test]
+    fn measured_range() {
+        let mut list = list(10, RowHeight::Measured(Size::new(SizeUnit::Pixels, 20.0)));
+        list.overscan = 0;
+        list.recompute_tops(20.0);
+        assert_eq!(list.visible_range(30.0, 20.0, 20.0), 1..3);
+        // Measured rows move the tops of the rows below.
+        list.measured[0] = Some(100.0);
+        list.measured[1] = Some(5.0);
+        list.tops.clear();
+        list.recompute_tops(20.0);
+        assert_eq!(list.top(2, 20.0), 105.0);
+        assert_eq!(list.top(10, 20.0), 265.0);
+        assert_eq!(list.visible_range(0.0, 99.0, 20.0), 0..1);
+        assert_eq!(list.visible_range(99.0, 10.0, 20.0), 0..3);
+        assert_eq!(list.visible_range(500.0, 10.0, 20.0), 9..10);
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::asset::{Assets, Handle};
+use bevy::math::Vec2;
+use bevy::text::Font;
+use bevy::window::{CursorIcon, Window};
+use bevy_rectray::dsl::prelude::*;
+use bevy_rectray::testing::TestApp;
+use bevy_rectray::widgets::button::{ButtonClick, ToggleChange};
+use bevy_rectray::widgets::drag::Dragging;
+use bevy_rectray::widgets::inputbox::TextChange;
+use bevy_rectray::widgets::util::SetCursor;
+use bevy_rectray::RotatedRect;
+
+fn load_font(app: &mut TestApp) -> Handle<Font> {
+    let bytes = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/RobotoCondensed.ttf"))
+        .expect("Could not read font.");
+    let font = Font::try_from_bytes(bytes).expect("Could not parse font.");
+    app.world().resource_mut::<Assets<Font>>().add(font)
+}
+
+#[test]
+fn button_click() {
+    let mut app = TestApp::new();
+    let button = app.spawn(|commands| button!(commands {
+        dimension: [100, 40],
+        event: EventFlags::LeftClick,
+    }));
+    let clicked = app.probe::<ButtonClick>(button);
+    app.move_cursor([300.0, 200.0]);
+    clicked.assert_not_sent();
+    app.click(button);
+    assert_eq!(clicked.count(), 1);
+}
+
+#[test]
+fn check_button_toggle() {
+    let mut app = TestApp::new();
+    let button = app.spawn(|commands| check_button!(commands {
+        dimension: [100, 40],
+        checked: false,
+    }));
+    let toggled = app.probe::<ToggleChange>(button);
+    app.click(button);
+    toggled.assert_last(true);
+    app.click(button);
+    toggled.assert_last(false);
+    assert_eq!(toggled.count(), 2);
+}
+
+#[test]
+fn inputbox_typing() {
+    let mut app = TestApp::new();
+    let font = load_font(&mut app);
+    let input = app.spawn(|commands| inputbox!(commands {
+        dimension: [400, 40],
+        font_size: 32,
+        hitbox: Hitbox::rect(1),
+        text: "Hello",
+        font: font,
+        color: color!(red),
+        cursor_bar: rectangle! {
+            dimension: size2!(2, 1 em),
+        },
+        cursor_area: rectangle! {
+            dimension: size2!(12, 1 em),
+        },
+    }));
+    let changed = app.probe::<TextChange>(input);
+    app.type_text("ignored");
+    changed.assert_not_sent();
+    // Clicks within the double click threshold of startup count as double clicks,
+    // which select all.
+    app.step(60);
+    // Focus and move the cursor to the end.
+    app.click([199.0, 0.0]);
+    app.type_text(", World!");
+    changed.assert_last("Hello, World!".to_owned());
+}
+
+#[test]
+fn drag_moves_draggable() {
+    let mut app = TestApp::new();
+    let item = app.spawn(|commands| frame!(commands {
+        dimension: [50, 50],
+        hitbox: Hitbox::rect(1),
+        event: EventFlags::LeftDrag,
+        extra: Dragging::BOTH,
+    }));
+    app.drag(item, [100.0, 50.0]);
+    app.step(1);
+    let center = app.world().get::<RotatedRect>(item).unwrap().center();
+    assert!((center - Vec2::new(100.0, 50.0)).length() < 1.0, "{center}");
+}
+
+#[test]
+fn cursor_icon_resets_on_leaving_window() {
+    let mut app = TestApp::new();
+    let primary = app.window();
+    let button = app.spawn(|commands| frame!(commands {
+        dimension: [100, 40],
+        event: EventFlags::Hover,
+        extra: SetCursor { flags: EventFlags::Hover, icon: CursorIcon::Grab },
+    }));
+    app.move_cursor(button);
+    app.step(1);
+    assert_eq!(app.world().get::<Window>(primary).unwrap().cursor.icon, CursorIcon::Grab);
+
+    let other = app.spawn_window(400.0, 300.0);
+    app.set_window(other).move_cursor([0.0, 0.0]);
+    app.step(1);
+    assert_eq!(app.world().get::<Window>(primary).unwrap().cursor.icon, CursorIcon::Default);
+}
@@ -0,0 +1,28 @@
+use bevy::ecs::component::Component;
+use bevy_rectray::anim::{Easing, Interpolate};
+use bevy_rectray::interpolate_field;
+use bevy_rectray::testing::TestApp;
+
+#[derive(Debug, Component)]
+struct Circle {
+    radius: f32,
+}
+
+interpolate_field! {
+    Radius(f32): Circle { radius };
+}
+
+#[test]
+fn interpolate_user_field() {
+    let mut app = TestApp::new();
+    // Adding the plugin twice is allowed.
+    app.app.add_plugins((Radius::plugin(), Radius::plugin()));
+    let mut interpolate = Interpolate::<Radius>::new(Easing::Linear, 0.0, 1.0);
+    interpolate.interpolate_to(10.0);
+    let circle = app.world().spawn((Circle { radius: 0.0 }, interpolate)).id();
+    app.step(30);
+    let radius = app.world().get::<Circle>(circle).unwrap().radius;
+    assert!(radius > 0.0 && radius < 10.0, "{radius}");
+    app.step(60);
+    assert_eq!(app.world().get::<Circle>(circle).unwrap().radius, 10.0);
+}
@@ -0,0 +1,129 @@
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::Entity;
+use bevy::ecs::system::Resource;
+use bevy::hierarchy::Children;
+use bevy_rectray::anim::Easing;
+use bevy_rectray::bundles::RectrayBundle;
+use bevy_rectray::testing::TestApp;
+use bevy_rectray::util::{RCommands, WidgetBuilder, WorldExtension};
+use bevy_rectray::widgets::list::{ListBinding, ListExit, ListResource};
+
+type Item = (u32, &'static str);
+
+#[derive(Debug, Component)]
+struct Label(&'static str);
+
+#[derive(Debug, Default, Resource)]
+struct Items(Vec<Item>);
+
+impl ListResource for Items {
+    type Item = Item;
+
+    fn items(&self) -> &[Self::Item] {
+        &self.0
+    }
+}
+
+fn builder() -> WidgetBuilder<Item> {
+    WidgetBuilder::new(|commands: &mut RCommands, (_, label): Item| {
+        commands.spawn_bundle((RectrayBundle::default(), Label(label))).id()
+    })
+}
+
+fn setup(app: &mut TestApp, binding: ListBinding<Item>) -> Entity {
+    app.app.register_list_binding::<Item>();
+    let entity = app.world().spawn((RectrayBundle::default(), binding)).id();
+    app.step(1);
+    entity
+}
+
+fn set(app: &mut TestApp, entity: Entity, items: impl IntoIterator<Item = Item>) {
+    app.world().get_mut::<ListBinding<Item>>(entity).unwrap().set(items);
+    app.step(1);
+}
+
+fn children(app: &mut TestApp, entity: Entity) -> Vec<Entity> {
+    app.world().get::<Children>(entity).map(|x| x.to_vec()).unwrap_or_default()
+}
+
+fn entity_of(app: &mut TestApp, entity: Entity, key: u32) -> Entity {
+    app.world().get::<ListBinding<Item>>(entity).unwrap().entity_of(&key).unwrap()
+}
+
+fn labels(app: &mut TestApp, entity: Entity) -> Vec<&'static str> {
+    children(app, entity).into_iter()
+        .map(|child| app.world().get::<Label>(child).unwrap().0)
+        .collect()
+}
+
+#[test]
+fn keyed_insert() {
+    let mut app = TestApp::new();
+    let list = setup(&mut app, ListBinding::new(builder()).with_items([(1, "a"), (2, "b")]));
+    let (a, b) = (entity_of(&mut app, list, 1), entity_of(&mut app, list, 2));
+    set(&mut app, list, [(1, "a"), (3, "c"), (2, "b")]);
+    assert_eq!(labels(&mut app, list), ["a", "c", "b"]);
+    let c = entity_of(&mut app, list, 3);
+    assert_eq!(children(&mut app, list), [a, c, b]);
+}
+
+#[test]
+fn keyed_remove() {
+    let mut app = TestApp::new();
+    let list = setup(&mut app, ListBinding::new(builder()).with_items([(1, "a"), (2, "b"), (3, "c")]));
+    let (a, b, c) = (entity_of(&mut app, list, 1), entity_of(&mut app, list, 2), entity_of(&mut app, list, 3));
+    set(&mut app, list, [(1, "a"), (3, "c")]);
+    assert_eq!(children(&mut app, list), [a, c]);
+    assert!(app.world().get_entity(b).is_none());
+}
+
+#[test]
+fn keyed_reorder() {
+    let mut app = TestApp::new();
+    let list = setup(&mut app, ListBinding::new(builder()).with_items([(1, "a"), (2, "b"), (3, "c")]));
+    let (a, b, c) = (entity_of(&mut app, list, 1), entity_of(&mut app, list, 2), entity_of(&mut app, list, 3));
+    set(&mut app, list, [(3, "c"), (1, "a"), (2, "b")]);
+    assert_eq!(children(&mut app, list), [c, a, b]);
+    assert_eq!(labels(&mut app, list), ["c", "a", "b"]);
+}
+
+#[test]
+fn changed_values_exit_with_transition() {
+    let mut app = TestApp::new();
+    let binding = ListBinding::new(builder())
+        .with_items([(1, "a"), (2, "b")])
+        .with_transition(Easing::Linear, 0.5);
+    let list = setup(&mut app, binding);
+    let (a, b) = (entity_of(&mut app, list, 1), entity_of(&mut app, list, 2));
+    set(&mut app, list, [(1, "x")]);
+    let x = entity_of(&mut app, list, 1);
+    assert_ne!(x, a);
+    // Exiting children are kept at the end until their transition finishes.
+    assert_eq!(children(&mut app, list)[0], x);
+    assert!(app.world().get::<ListExit>(a).is_some());
+    assert!(app.world().get::<ListExit>(b).is_some());
+    app.step(60);
+    assert_eq!(children(&mut app, list), [x]);
+    assert!(app.world().get_entity(a).is_none());
+    assert!(app.world().get_entity(b).is_none());
+}
+
+#[test]
+fn list_resource() {
+    let mut app = TestApp::new();
+    // Registering twice, or registering the binding as well, is harmless.
+    app.app.register_list_resource::<Items>()
+        .register_list_resource::<Items>()
+        .register_list_binding::<Item>();
+    // Missing resources are ignored.
+    let list = app.world().spawn((RectrayBundle::default(), ListBinding::new(builder()).from_resource::<Items>())).id();
+    app.step(1);
+    assert!(children(&mut app, list).is_empty());
+
+    app.world().insert_resource(Items(vec![(1, "a"), (2, "b")]));
+    app.step(1);
+    assert_eq!(labels(&mut app, list), ["a", "b"]);
+    app.world().resource_mut::<Items>().0.reverse();
+    app.step(1);
+    assert_eq!(labels(&mut app, list), ["b", "a"]);
+}
@@ -0,0 +1,151 @@
+use bevy::asset::{Assets, Handle};
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::Entity;
+use bevy::hierarchy::Parent;
+use bevy::render::color::Color;
+use bevy_rectray::asset::{UiAsset, UiAssetRoot, WidgetKind, WidgetNode};
+use bevy_rectray::testing::TestApp;
+use bevy_rectray::{Coloring, Hitbox, Opacity, Size2, Transform2D};
+
+#[derive(Debug, Component)]
+struct State(u32);
+
+fn node(name: &str, children: Vec<WidgetNode>) -> WidgetNode {
+    WidgetNode {
+        name: name.to_owned(),
+        children,
+        ..Default::default()
+    }
+}
+
+fn rectangle(name: &str, color: Color) -> WidgetNode {
+    WidgetNode {
+        name: name.to_owned(),
+        widget: WidgetKind::Rectangle { size: None },
+        color: Some(color),
+        ..Default::default()
+    }
+}
+
+fn setup(app: &mut TestApp, root: WidgetNode) -> (Entity, Handle<UiAsset>) {
+    let handle = app.world().resource_mut::<Assets<UiAsset>>().add(UiAsset { root });
+    let entity = app.world().spawn(UiAssetRoot::new(handle.clone())).id();
+    app.step(2);
+    (entity, handle)
+}
+
+fn modify(app: &mut TestApp, handle: &Handle<UiAsset>, f: impl FnOnce(&mut WidgetNode)) {
+    let mut assets = app.world().resource_mut::<Assets<UiAsset>>();
+    f(&mut assets.get_mut(handle).unwrap().root);
+    app.step(2);
+}
+
+fn entity_at(app: &mut TestApp, root: Entity, path: &str) -> Option<Entity> {
+    app.world().get::<UiAssetRoot>(root).unwrap().entity_at(path)
+}
+
+#[test]
+fn reload_keeps_unchanged_entities() {
+    let mut app = TestApp::new();
+    let (root, handle) = setup(&mut app, node("menu", vec![
+        rectangle("a", Color::RED),
+        rectangle("b", Color::GREEN),
+    ]));
+    let menu = entity_at(&mut app, root, "").unwrap();
+    let a = entity_at(&mut app, root, "a").unwrap();
+    let b = entity_at(&mut app, root, "b").unwrap();
+    app.world().entity_mut(a).insert(State(1));
+
+    modify(&mut app, &handle, |root| {
+        root.children[0].color = Some(Color::BLUE);
+        root.children.remove(1);
+        root.children.push(rectangle("c", Color::WHITE));
+    });
+
+    assert_eq!(entity_at(&mut app, root, ""), Some(menu));
+    assert_eq!(entity_at(&mut app, root, "a"), Some(a));
+    assert_eq!(app.world().get::<State>(a).unwrap().0, 1);
+    assert_eq!(app.world().get::<Coloring>(a).unwrap().color, Color::BLUE);
+    assert!(app.world().get_entity(b).is_none());
+    let c = entity_at(&mut app, root, "c").unwrap();
+    assert_eq!(app.world().get::<Parent>(c).unwrap().get(), menu);
+}
+
+#[test]
+fn reload_does_not_leak_entities() {
+    let mut app = TestApp::new();
+    let (_, handle) = setup(&mut app, node("menu", vec![
+        rectangle("a", Color::RED),
+    ]));
+    let count = app.world().entities().len();
+    for color in [Color::BLUE, Color::GREEN, Color::RED] {
+        modify(&mut app, &handle, |root| root.children[0].color = Some(color));
+    }
+    assert_eq!(app.world().entities().len(), count);
+}
+
+#[test]
+fn reload_keeps_runtime_offset() {
+    let mut app = TestApp::new();
+    let (root, handle) = setup(&mut app, node("menu", vec![
+        rectangle("a", Color::RED),
+    ]));
+    let a = entity_at(&mut app, root, "a").unwrap();
+    // Like `Scrolling` moving its content.
+    app.world().get_mut::<Transform2D>(a).unwrap().offset = Size2::pixels(0.0, 40.0);
+
+    modify(&mut app, &handle, |root| root.children[0].color = Some(Color::BLUE));
+    assert_eq!(app.world().get::<Transform2D>(a).unwrap().offset, Size2::pixels(0.0, 40.0));
+
+    modify(&mut app, &handle, |root| root.children[0].offset = Size2::pixels(10.0, 0.0));
+    assert_eq!(app.world().get::<Transform2D>(a).unwrap().offset, Size2::pixels(10.0, 0.0));
+}
+
+#[test]
+fn reload_rebuilds_changed_widget_kind() {
+    let mut app = TestApp::new();
+    let (root, handle) = setup(&mut app, node("menu", vec![
+        rectangle("a", Color::RED),
+    ]));
+    let a = entity_at(&mut app, root, "a").unwrap();
+    modify(&mut app, &handle, |root| root.children[0].widget = WidgetKind::Frame);
+    let rebuilt = entity_at(&mut app, root, "a").unwrap();
+    assert_ne!(rebuilt, a);
+    assert!(app.world().get_entity(a).is_none());
+}
+
+#[test]
+fn reload_only_patches_changed_fields() {
+    let mut app = TestApp::new();
+    let (root, handle) = setup(&mut app, node("menu", vec![
+        rectangle("a", Color::RED),
+    ]));
+    let a = entity_at(&mut app, root, "a").unwrap();
+    // Runtime changes not described by the asset.
+    app.world().get_mut::<Opacity>(a).unwrap().opacity = 0.5;
+    app.world().entity_mut(a).insert(Hitbox::FULL);
+
+    modify(&mut app, &handle, |root| root.children[0].color = Some(Color::BLUE));
+    assert_eq!(app.world().get::<Coloring>(a).unwrap().color, Color::BLUE);
+    assert_eq!(app.world().get::<Opacity>(a).unwrap().opacity, 0.5);
+    assert_eq!(app.world().get::<Hitbox>(a), Some(&Hitbox::FULL));
+
+    modify(&mut app, &handle, |root| root.children[0].opacity = Some(0.8));
+    assert_eq!(app.world().get::<Opacity>(a).unwrap().opacity, 0.8);
+    assert_eq!(app.world().get::<Coloring>(a).unwrap().color, Color::BLUE);
+}
+
+#[test]
+fn reload_removes_components_of_removed_fields() {
+    let mut app = TestApp::new();
+    let (root, handle) = setup(&mut app, node("menu", vec![
+        WidgetNode {
+            hitbox: Some(Hitbox::FULL),
+            ..rectangle("a", Color::RED)
+        },
+    ]));
+    let a = entity_at(&mut app, root, "a").unwrap();
+    assert!(app.world().get::<Hitbox>(a).is_some());
+    modify(&mut app, &handle, |root| root.children[0].hitbox = None);
+    assert!(app.world().get::<Hitbox>(a).is_none());
+}
@@ -0,0 +1,90 @@
+use bevy::app::PostUpdate;
+use bevy::hierarchy::BuildWorldChildren;
+use bevy::math::{Vec2, Vec3};
+use bevy::render::camera::{camera_system, Camera, OrthographicProjection, PerspectiveProjection};
+use bevy::render::view::Visibility;
+use bevy::transform::components::{GlobalTransform, Transform};
+use bevy_rectray::bundles::RectrayBundle;
+use bevy_rectray::testing::TestApp;
+use bevy_rectray::{RectrayRoot, RootEntity};
+
+#[test]
+fn projected_root_hides_behind_camera() {
+    let mut app = TestApp::new();
+    app.app.add_systems(PostUpdate, camera_system::<PerspectiveProjection>);
+    let camera = app.world().spawn((
+        Camera { order: -1, ..Default::default() },
+        PerspectiveProjection::default(),
+        Transform::from_xyz(0.0, 0.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        GlobalTransform::default(),
+    )).id();
+    let target = app.world().spawn((Transform::default(), GlobalTransform::default())).id();
+    let child = app.world().spawn(RectrayBundle::default()).id();
+    let root = app.world().spawn((
+        RectrayBundle::default(),
+        RectrayRoot::projected(target, camera).with_size(Vec2::new(40.0, 20.0)),
+    )).push_children(&[child]).id();
+    app.step(2);
+    assert_eq!(app.world().get::<Visibility>(root), Some(&Visibility::Inherited));
+
+    app.world().get_mut::<Transform>(target).unwrap().translation = Vec3::new(0.0, 0.0, 20.0);
+    app.step(2);
+    assert_eq!(app.world().get::<Visibility>(root), Some(&Visibility::Hidden));
+    assert_eq!(app.world().get::<Visibility>(child), Some(&Visibility::Inherited));
+
+    app.world().get_mut::<Transform>(target).unwrap().translation = Vec3::ZERO;
+    app.step(2);
+    assert_eq!(app.world().get::<Visibility>(root), Some(&Visibility::Inherited));
+}
+
+#[test]
+fn clamped_projected_root_stays_visible() {
+    let mut app = TestApp::new();
+    app.app.add_systems(PostUpdate, camera_system::<PerspectiveProjection>);
+    let camera = app.world().spawn((
+        Camera { order: -1, ..Default::default() },
+        PerspectiveProjection::default(),
+        Transform::from_xyz(0.0, 0.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        GlobalTransform::default(),
+    )).id();
+    let target = app.world().spawn((Transform::from_xyz(0.0, 0.0, 20.0), GlobalTransform::default())).id();
+    let root = app.world().spawn((
+        RectrayBundle::default(),
+        RectrayRoot::projected(target, camera).clamped(),
+    )).id();
+    app.step(2);
+    assert_eq!(app.world().get::<Visibility>(root), Some(&Visibility::Inherited));
+}
+
+#[test]
+fn entities_under_other_parents_stay_visible() {
+    let mut app = TestApp::new();
+    let child = app.world().spawn(RectrayBundle::default()).id();
+    app.world().spawn((Transform::default(), GlobalTransform::default(), Visibility::default()))
+        .push_children(&[child]);
+    app.step(2);
+    assert_eq!(app.world().get::<Visibility>(child), Some(&Visibility::Inherited));
+}
+
+#[test]
+fn layout_records_root_entity() {
+    let mut app = TestApp::new();
+    let camera = app.world().spawn((
+        Camera { order: -1, ..Default::default() },
+        OrthographicProjection::default(),
+        GlobalTransform::default(),
+    )).id();
+    let grandchild = app.world().spawn(RectrayBundle::default()).id();
+    let child = app.world().spawn(RectrayBundle::default()).push_children(&[grandchild]).id();
+    let root = app.world().spawn((RectrayBundle::default(), RectrayRoot::Camera(camera)))
+        .push_children(&[child]).id();
+    let other = app.world().spawn(RectrayBundle::default()).id();
+    app.step(2);
+    assert_eq!(app.world().get::<RootEntity>(root), Some(&RootEntity(Some(root))));
+    assert_eq!(app.world().get::<RootEntity>(grandchild), Some(&RootEntity(Some(root))));
+    assert_eq!(app.world().get::<RootEntity>(other), Some(&RootEntity(None)));
+
+    app.world().entity_mut(root).remove::<RectrayRoot>();
+    app.step(2);
+    assert_eq!(app.world().get::<RootEntity>(grandchild), Some(&RootEntity(None)));
+}
@@ -0,0 +1,86 @@
+use bevy::core::Name;
+use bevy::ecs::world::World;
+use bevy::hierarchy::BuildWorldChildren;
+use bevy::math::Vec2;
+use bevy_rectray::layout::{Container, LayoutRange, StackLayout};
+use bevy_rectray::testing::{assert_snapshot, compute_layout, dump_layout, dump_layout_json};
+use bevy_rectray::bundles::RectrayBundle;
+use bevy_rectray::{Anchor, Dimension, Opacity, Size2, Transform2D};
+
+macro_rules! snapshot_path {
+    ($name: literal) => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/", $name)
+    };
+}
+
+#[test]
+fn snapshot_root() {
+    let mut world = World::new();
+    world.spawn((
+        Name::new("root"),
+        RectrayBundle {
+            dimension: Dimension::pixels(Vec2::new(100.0, 100.0)),
+            ..Default::default()
+        }
+    ));
+    compute_layout(&mut world, Vec2::new(800.0, 600.0));
+    assert_snapshot(snapshot_path!("root.txt"), &dump_layout(&mut world));
+}
+
+fn hstack(world: &mut World) {
+    let items: Vec<_> = [
+        ("a", 40.0, 1.0),
+        ("b", 60.0, 0.5),
+        ("c", 20.0, 1.0),
+    ].into_iter().map(|(name, width, opacity)| world.spawn((
+        Name::new(name),
+        RectrayBundle {
+            dimension: Dimension::pixels(Vec2::new(width, 20.0)),
+            opacity: Opacity::new(opacity),
+            ..Default::default()
+        }
+    )).id()).collect();
+    world.spawn((
+        Name::new("stack"),
+        RectrayBundle {
+            transform: Transform2D::UNIT.with_anchor(Anchor::TOP_LEFT).with_offset(Size2::pixels(10.0, -10.0)),
+            dimension: Dimension::pixels(Vec2::new(200.0, 40.0)),
+            ..Default::default()
+        },
+        Container {
+            layout: StackLayout::HSTACK.into(),
+            margin: Size2::pixels(5.0, 0.0),
+            padding: Size2::pixels(4.0, 4.0),
+            range: LayoutRange::All,
+            maximum: 0,
+        },
+    )).push_children(&items);
+}
+
+#[test]
+fn snapshot_hstack() {
+    let mut world = World::new();
+    hstack(&mut world);
+    compute_layout(&mut world, Vec2::new(800.0, 600.0));
+    assert_snapshot(snapshot_path!("hstack.txt"), &dump_layout(&mut world));
+}
+
+#[test]
+fn snapshot_hstack_json() {
+    let mut world = World::new();
+    hstack(&mut world);
+    compute_layout(&mut world, Vec2::new(800.0, 600.0));
+    assert_snapshot(snapshot_path!("hstack.json"), &dump_layout_json(&mut world));
+}
+
+#[test]
+fn snapshot_missing() {
+    // Updating would write the missing snapshot.
+    if std::env::var_os("RECTRAY_UPDATE_SNAPSHOTS").is_some() {
+        return;
+    }
+    let result = std::panic::catch_unwind(|| assert_snapshot(snapshot_path!("missing.txt"), ""));
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("missing, set RECTRAY_UPDATE_SNAPSHOTS"));
+    assert!(!std::path::Path::new(snapshot_path!("missing.txt")).exists());
+}
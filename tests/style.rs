@@ -0,0 +1,104 @@
+use bevy::ecs::change_detection::{DetectChanges, DetectChangesMut};
+use bevy::ecs::entity::Entity;
+use bevy::hierarchy::BuildWorldChildren;
+use bevy::render::color::Color;
+use bevy_rectray::bundles::RectrayBundle;
+use bevy_rectray::style::{Style, Styled, Theme};
+use bevy_rectray::testing::TestApp;
+use bevy_rectray::{Coloring, Hitbox};
+
+fn theme() -> Theme {
+    Theme::new()
+        .with("panel", Style {
+            color: Some(Color::RED),
+            ..Default::default()
+        })
+        .with("button", Style {
+            hitbox: Some(Hitbox::default()),
+            ..Default::default()
+        })
+}
+
+fn spawn(app: &mut TestApp, parent: Option<Entity>, styled: Option<&str>) -> Entity {
+    let mut entity = app.world().spawn((RectrayBundle::default(), Coloring::new(Color::WHITE)));
+    if let Some(name) = styled {
+        entity.insert(Styled::new(name));
+    }
+    let entity = entity.id();
+    if let Some(parent) = parent {
+        app.world().entity_mut(parent).add_child(entity);
+    }
+    entity
+}
+
+fn color(app: &mut TestApp, entity: Entity) -> Color {
+    app.world().get::<Coloring>(entity).unwrap().color
+}
+
+#[test]
+fn inherit_through_unstyled() {
+    let mut app = TestApp::new();
+    app.world().insert_resource(theme());
+    let panel = spawn(&mut app, None, Some("panel"));
+    let middle = spawn(&mut app, Some(panel), None);
+    let button = spawn(&mut app, Some(middle), Some("button"));
+    app.step(1);
+    assert_eq!(color(&mut app, panel), Color::RED);
+    assert_eq!(color(&mut app, middle), Color::RED);
+    assert_eq!(color(&mut app, button), Color::RED);
+    assert_eq!(app.world().get::<Hitbox>(button), Some(&Hitbox::default()));
+    assert_eq!(app.world().get::<Hitbox>(middle), None);
+}
+
+#[test]
+fn unstyled_spawned_later() {
+    let mut app = TestApp::new();
+    app.world().insert_resource(theme());
+    let panel = spawn(&mut app, None, Some("panel"));
+    app.step(1);
+    let child = spawn(&mut app, Some(panel), None);
+    app.step(1);
+    assert_eq!(color(&mut app, child), Color::RED);
+}
+
+#[test]
+fn theme_switch() {
+    let mut app = TestApp::new();
+    app.world().insert_resource(theme());
+    let panel = spawn(&mut app, None, Some("panel"));
+    let child = spawn(&mut app, Some(panel), None);
+    app.step(1);
+    app.world().resource_mut::<Theme>().switch(Theme::new().with("panel", Style {
+        color: Some(Color::BLUE),
+        ..Default::default()
+    }));
+    app.step(1);
+    assert_eq!(color(&mut app, panel), Color::BLUE);
+    assert_eq!(color(&mut app, child), Color::BLUE);
+}
+
+#[test]
+fn only_changed_subtree() {
+    let mut app = TestApp::new();
+    app.world().insert_resource(theme()
+        .with("other", Style {
+            color: Some(Color::GREEN),
+            ..Default::default()
+        }));
+    let a = spawn(&mut app, None, Some("panel"));
+    let b = spawn(&mut app, None, Some("panel"));
+    let button = spawn(&mut app, Some(b), Some("button"));
+    app.step(1);
+    let hitbox_tick = app.world().entity(button).get_ref::<Hitbox>().unwrap().last_changed();
+    // Manual changes outside of the changed subtree are kept.
+    app.world().get_mut::<Coloring>(b).unwrap().color = Color::WHITE;
+    *app.world().get_mut::<Styled>(a).unwrap() = Styled::new("other");
+    app.step(1);
+    assert_eq!(color(&mut app, a), Color::GREEN);
+    assert_eq!(color(&mut app, b), Color::WHITE);
+    // Re-applying the theme does not touch unchanged hitboxes.
+    app.world().resource_mut::<Theme>().set_changed();
+    app.step(1);
+    assert_eq!(color(&mut app, b), Color::RED);
+    assert_eq!(app.world().entity(button).get_ref::<Hitbox>().unwrap().last_changed(), hitbox_tick);
+}
@@ -0,0 +1,49 @@
+use bevy::asset::{Assets, Handle};
+use bevy::math::Vec2;
+use bevy::render::texture::Image;
+use bevy::sprite::Sprite;
+use bevy::text::Font;
+use bevy_rectray::dsl::prelude::*;
+use bevy_rectray::testing::TestApp;
+use bevy_rectray::widgets::TextFragment;
+use bevy_rectray::DimensionData;
+
+fn load_font(app: &mut TestApp) -> Handle<Font> {
+    let bytes = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/RobotoCondensed.ttf"))
+        .expect("Could not read font.");
+    let font = Font::try_from_bytes(bytes).expect("Could not parse font.");
+    app.world().resource_mut::<Assets<Font>>().add(font)
+}
+
+fn image(app: &mut TestApp) -> Handle<Image> {
+    app.world().resource_mut::<Assets<Image>>().add(Image::default())
+}
+
+#[test]
+fn copied_dimension_sized_by_text() {
+    let mut app = TestApp::new();
+    let font = load_font(&mut app);
+    let image = image(&mut app);
+    let sprite = app.spawn(|commands| sprite!(commands {
+        sprite: image,
+        font_size: 24,
+        extra: TextFragment::new("Hello, world!").with_font(font),
+    }));
+    app.step(2);
+    let size = app.world().get::<Sprite>(sprite).unwrap().custom_size.unwrap();
+    assert!(size.x > 24.0 && size.y > 12.0, "{size}");
+    assert_eq!(app.world().get::<DimensionData>(sprite).unwrap().size, size);
+}
+
+#[test]
+fn copied_dimension_ignores_custom_size_of_sprites() {
+    let mut app = TestApp::new();
+    let image = image(&mut app);
+    let sprite = app.spawn(|commands| sprite!(commands {
+        sprite: image,
+        size: [50, 50],
+    }));
+    app.step(2);
+    // Sized by the 1x1 image.
+    assert_eq!(app.world().get::<DimensionData>(sprite).unwrap().size, Vec2::ONE);
+}
@@ -0,0 +1,97 @@
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::Entity;
+use bevy::ecs::world::World;
+use bevy::hierarchy::BuildWorldChildren;
+use bevy::math::Vec2;
+use bevy_rectray::bundles::RectrayBundle;
+use bevy_rectray::testing::TestApp;
+use bevy_rectray::util::{RCommands, WidgetBuilder};
+use bevy_rectray::widgets::virtual_list::{RowHeight, VirtualList};
+use bevy_rectray::{Anchor, Dimension, Opacity, Size, Size2, SizeUnit, Transform2D};
+
+#[derive(Debug, Component)]
+struct Row(usize);
+
+fn setup(app: &mut TestApp, list: VirtualList) -> Entity {
+    let list = app.world().spawn((
+        RectrayBundle {
+            transform: Transform2D::UNIT.with_anchor(Anchor::TOP_CENTER),
+            ..Default::default()
+        },
+        list,
+    )).id();
+    app.world().spawn(RectrayBundle {
+        dimension: Dimension::pixels(Vec2::new(100.0, 100.0)),
+        ..Default::default()
+    }).push_children(&[list]);
+    app.step(2);
+    list
+}
+
+fn builder() -> WidgetBuilder<usize> {
+    WidgetBuilder::new(|commands: &mut RCommands, index: usize| {
+        commands.spawn_bundle((RectrayBundle::default(), Row(index))).id()
+    })
+}
+
+fn rebind(world: &mut World, row: Entity, index: usize) {
+    world.get_mut::<Row>(row).unwrap().0 = index;
+}
+
+fn spawned(app: &mut TestApp, list: Entity) -> Vec<(usize, Entity)> {
+    app.world().get::<VirtualList>(list).unwrap().spawned().collect()
+}
+
+fn scroll(app: &mut TestApp, list: Entity, y: f32) {
+    app.world().get_mut::<Transform2D>(list).unwrap().offset = Size2::pixels(0.0, y);
+    app.step(2);
+}
+
+fn height() -> RowHeight {
+    RowHeight::Fixed(Size::new(SizeUnit::Pixels, 20.0))
+}
+
+#[test]
+fn rows_are_recycled() {
+    let mut app = TestApp::new();
+    let list = setup(&mut app, VirtualList::new(1000, builder(), height()).with_rebind(rebind));
+    let before = spawned(&mut app, list);
+    assert_eq!(before.iter().map(|(i, _)| *i).collect::<Vec<_>>(), (0..7).collect::<Vec<_>>());
+    let entities = app.world().entities().len();
+
+    scroll(&mut app, list, 500.0);
+    let after = spawned(&mut app, list);
+    assert_eq!(after.iter().map(|(i, _)| *i).collect::<Vec<_>>(), (24..32).collect::<Vec<_>>());
+    // One more row is visible, the rest are reused.
+    assert_eq!(app.world().entities().len(), entities + 1);
+    for (index, row) in after {
+        assert_eq!(app.world().get::<Row>(row).unwrap().0, index);
+        assert_eq!(app.world().get::<Opacity>(row).unwrap().opacity, 1.0);
+    }
+
+    scroll(&mut app, list, 0.0);
+    assert_eq!(app.world().entities().len(), entities + 1);
+    let visible: Vec<_> = spawned(&mut app, list).into_iter().map(|(_, e)| e).collect();
+    assert_eq!(visible.len(), 7);
+    let pooled: Vec<_> = app.world().query::<(Entity, &Row, &Opacity)>()
+        .iter(app.world())
+        .filter(|(entity, ..)| !visible.contains(entity))
+        .map(|(_, _, opacity)| (opacity.opacity, opacity.disabled))
+        .collect();
+    assert_eq!(pooled, [(0.0, true)]);
+}
+
+#[test]
+fn rows_are_rebuilt_without_rebind() {
+    let mut app = TestApp::new();
+    let list = setup(&mut app, VirtualList::new(1000, builder(), height()));
+    let before: Vec<_> = spawned(&mut app, list).into_iter().map(|(_, e)| e).collect();
+    scroll(&mut app, list, 500.0);
+    for (index, row) in spawned(&mut app, list) {
+        assert!(!before.contains(&row));
+        assert_eq!(app.world().get::<Row>(row).unwrap().0, index);
+    }
+    for row in before {
+        assert!(app.world().get_entity(row).is_none());
+    }
+}